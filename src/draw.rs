@@ -0,0 +1,589 @@
+use crate::{
+    CompoundPart, Item, Shape, TextureId, World, color_from_seed, physics::rounded_rectangle_points,
+};
+use glam::{Affine2, Vec2};
+use phy::Solver;
+use rgb::Rgb;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DrawMode {
+    #[default]
+    Normal,
+    Debug,
+    /// Normal drawing with items tinted by [collision layer](crate::Body::layer).
+    LayerView,
+}
+
+/// Distinct color of a collision layer bitmask.
+pub fn layer_color(layer: u32) -> Rgb<f32> {
+    color_from_seed(layer as u64)
+}
+
+/// Drawing border thickness factor
+pub(crate) const BORDERX: f32 = 1.0 / 24.0;
+
+/// Draw order of the area within walls.
+const WALLS_ORDER: i32 = -1000;
+/// Draw order of background grid lines, drawn above the walls area.
+const GRID_ORDER: i32 = WALLS_ORDER + 1;
+/// The lowest draw order of an item that keeps it above the background.
+pub(crate) const MIN_ITEM_ORDER: i32 = GRID_ORDER + 1;
+
+/// Default number of debug circle outline segments for a circle of [`SEGMENTS_RADIUS`].
+pub const CIRCLE_SEGMENTS: usize = 32;
+/// Reference radius for circle outline segment count, count is proportional to radius.
+pub const SEGMENTS_RADIUS: f32 = 0.25;
+
+/// Default screen size in pixels of a unit of world half-size, see [`crate::WorldView::scale`].
+pub const RENDER_SCALE: f32 = 640.0;
+
+/// Minimal screen distance in pixels between background grid lines.
+const MIN_GRID_PIXELS: f32 = 4.0;
+
+const WHITE: Rgb<f32> = Rgb::new(1.0, 1.0, 1.0);
+const BLACK: Rgb<f32> = Rgb::new(0.0, 0.0, 0.0);
+
+/// How the area enclosed by walls is filled.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum BackgroundStyle {
+    /// Plain wall color.
+    #[default]
+    Solid,
+    /// Wall color with lines through the world center at `spacing` interval.
+    ///
+    /// Lines closer than a few pixels on screen are thinned out to a multiple of `spacing`.
+    Grid { spacing: f32, color: Rgb<f32> },
+}
+
+/// Colors of the scene.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Theme {
+    /// Color outside of the walls.
+    pub background: Rgb<f32>,
+    /// Color of the area enclosed by walls, also used for debug wall outline.
+    pub wall: Rgb<f32>,
+    /// Thickness of debug wall outline relative to item border thickness factor.
+    pub wall_thickness_factor: f32,
+    /// Decoration of the area enclosed by walls, not drawn in debug mode.
+    pub background_style: BackgroundStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: Rgb::new(0.5, 0.5, 0.5),
+            wall: WHITE,
+            wall_thickness_factor: 0.3,
+            background_style: BackgroundStyle::default(),
+        }
+    }
+}
+
+/// Drawing primitive independent of rendering backend.
+#[derive(Clone, Debug)]
+pub enum RenderCommand {
+    /// Unit quad `[-1, 1]^2` transformed by `transform`, filled with texture multiplied by color.
+    Quad {
+        transform: Affine2,
+        texture_id: Option<TextureId>,
+        color: Rgb<f32>,
+        order: i32,
+    },
+    /// Filled axis-aligned rectangle.
+    Rect {
+        min: Vec2,
+        max: Vec2,
+        color: Rgb<f32>,
+        order: i32,
+    },
+    /// Line segment.
+    Line {
+        a: Vec2,
+        b: Vec2,
+        thickness: f32,
+        color: Rgb<f32>,
+        order: i32,
+    },
+}
+
+/// Closed polyline through `points`.
+fn outline(
+    points: &[Vec2],
+    thickness: f32,
+    color: Rgb<f32>,
+    order: i32,
+) -> impl Iterator<Item = RenderCommand> + '_ {
+    points
+        .iter()
+        .enumerate()
+        .map(move |(i, a)| RenderCommand::Line {
+            a: *a,
+            b: points[(i + 1) % points.len()],
+            thickness,
+            color,
+            order,
+        })
+}
+
+/// Vertices of regular `n`-gon inscribed into a circle, first vertex is at `angle`.
+fn circle_points(center: Vec2, radius: f32, angle: f32, n: usize) -> Vec<Vec2> {
+    (0..n)
+        .map(|i| {
+            let angle = angle + std::f32::consts::TAU * i as f32 / n as f32;
+            center + radius * Vec2::from_angle(angle)
+        })
+        .collect()
+}
+
+/// Number of outline segments for a circle of `radius`, never less than 3.
+fn segment_count(radius: f32, segments: usize) -> usize {
+    ((segments as f32 * radius / SEGMENTS_RADIUS).round() as usize).max(3)
+}
+
+impl Shape {
+    /// Draw order of items of this shape unless specified, circles are drawn above rectangles.
+    pub fn default_order(&self) -> i32 {
+        match self {
+            Shape::Circle { .. } => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Drawing commands of a simple part placed at `pos` rotated by `angle`.
+///
+/// Textured quad is scaled by `visual_scale`, outlines are not.
+fn simple_commands(
+    part: &CompoundPart,
+    pos: Vec2,
+    angle: f32,
+    visual_scale: f32,
+    order: i32,
+    mode: DrawMode,
+    circle_segments: usize,
+) -> Vec<RenderCommand> {
+    let mut commands = Vec::new();
+    let CompoundPart {
+        shape,
+        texture,
+        color,
+        ..
+    } = part;
+    let (texture, color) = (*texture, *color);
+    let size = match shape {
+        Shape::Circle { radius } => Vec2::splat(*radius),
+        Shape::Rectangle { size } => *size,
+        Shape::RoundedRectangle {
+            size,
+            corner_radius,
+        } => {
+            let rot = Vec2::from_angle(angle);
+            let r = corner_radius.clamp(0.0, size.min_element());
+            if mode != DrawMode::Debug {
+                // Cross of two quads, corners are filled by a thick line along the rim
+                for half in [Vec2::new(size.x, size.y - r), Vec2::new(size.x - r, size.y)] {
+                    commands.push(RenderCommand::Quad {
+                        transform: Affine2::from_scale_angle_translation(half, angle, pos),
+                        texture_id: texture,
+                        color,
+                        order,
+                    });
+                }
+                let rim = rounded_rectangle_points(*size - 0.5 * r, 0.5 * r)
+                    .iter()
+                    .map(|p| pos + rot.rotate(*p))
+                    .collect::<Vec<_>>();
+                commands.extend(outline(&rim, r, color, order));
+            }
+            let points = rounded_rectangle_points(*size, r)
+                .iter()
+                .map(|p| pos + rot.rotate(*p))
+                .collect::<Vec<_>>();
+            let color = match mode {
+                DrawMode::Normal | DrawMode::LayerView => BLACK,
+                DrawMode::Debug => color,
+            };
+            commands.extend(outline(&points, BORDERX * size.min_element(), color, order));
+            return commands;
+        }
+        Shape::Ring { outer, inner } => {
+            // Annulus is drawn as a thick loop, outer and inner boundaries in debug mode
+            let loops: &[(f32, f32)] = match mode {
+                DrawMode::Normal | DrawMode::LayerView => &[(0.5 * (outer + inner), outer - inner)],
+                DrawMode::Debug => &[(*outer, BORDERX * outer), (*inner, BORDERX * outer)],
+            };
+            for &(radius, thickness) in loops {
+                let points =
+                    circle_points(pos, radius, angle, segment_count(*outer, circle_segments));
+                commands.extend(outline(&points, thickness, color, order));
+            }
+            return commands;
+        }
+        Shape::Compound { .. } => return commands,
+    };
+    match mode {
+        DrawMode::Normal | DrawMode::LayerView => {
+            commands.push(RenderCommand::Quad {
+                transform: Affine2::from_scale_angle_translation(visual_scale * size, angle, pos),
+                texture_id: texture,
+                color,
+                order,
+            });
+        }
+        DrawMode::Debug => {
+            if let Shape::Circle { radius } = shape {
+                let n = segment_count(*radius, circle_segments);
+                let points = circle_points(pos, *radius, angle, n);
+                commands.extend(outline(&points, BORDERX * radius, color, order));
+            }
+        }
+    }
+    if let Shape::Rectangle { .. } = shape {
+        let rot = Vec2::from_angle(angle);
+        let points = [
+            Vec2::new(-size.x, -size.y),
+            Vec2::new(size.x, -size.y),
+            Vec2::new(size.x, size.y),
+            Vec2::new(-size.x, size.y),
+        ]
+        .map(|p| pos + rot.rotate(p));
+        let color = match mode {
+            DrawMode::Normal | DrawMode::LayerView => BLACK,
+            DrawMode::Debug => color,
+        };
+        commands.extend(outline(&points, BORDERX * size.min_element(), color, order));
+    }
+    commands
+}
+
+impl<S: Solver> Item<S> {
+    /// Model matrix mapping unit quad `[-1, 1]^2` onto the item.
+    ///
+    /// Scale is the half-size of the shape, compound items are not scaled.
+    pub fn transform(&self) -> Affine2 {
+        let size = match &self.shape {
+            Shape::Circle { radius } | Shape::Ring { outer: radius, .. } => Vec2::splat(*radius),
+            Shape::Rectangle { size } | Shape::RoundedRectangle { size, .. } => *size,
+            Shape::Compound { .. } => Vec2::ONE,
+        };
+        Affine2::from_scale_angle_translation(size, self.rot.angle(), *self.pos)
+    }
+
+    /// Drawing commands of the item.
+    ///
+    /// `circle_segments` is the number of debug circle outline segments for [`SEGMENTS_RADIUS`].
+    pub fn render_commands(&self, mode: DrawMode, circle_segments: usize) -> Vec<RenderCommand> {
+        self.render_commands_at(*self.pos, self.rot.angle(), mode, circle_segments)
+    }
+
+    /// Drawing commands of the item at [interpolated pose](Item::interpolated_pose).
+    pub fn render_commands_interpolated(
+        &self,
+        mode: DrawMode,
+        circle_segments: usize,
+        alpha: f32,
+    ) -> Vec<RenderCommand> {
+        let (pos, angle) = self.interpolated_pose(alpha);
+        self.render_commands_at(pos, angle, mode, circle_segments)
+    }
+
+    fn render_commands_at(
+        &self,
+        pos: Vec2,
+        angle: f32,
+        mode: DrawMode,
+        circle_segments: usize,
+    ) -> Vec<RenderCommand> {
+        let (mode, tint) = match mode {
+            DrawMode::LayerView => (DrawMode::Normal, Some(layer_color(self.layer))),
+            mode => (mode, None),
+        };
+        self.parts_at(pos, angle)
+            .into_iter()
+            .map(|(part, pos, angle)| {
+                let color = tint.unwrap_or(part.color);
+                (CompoundPart { color, ..part }, pos, angle)
+            })
+            .flat_map(|(part, pos, angle)| {
+                simple_commands(
+                    &part,
+                    pos,
+                    angle,
+                    self.visual_scale,
+                    self.order,
+                    mode,
+                    circle_segments,
+                )
+            })
+            .collect()
+    }
+}
+
+impl<S: Solver> World<S> {
+    /// Number of debug circle outline segments for a circle of [`SEGMENTS_RADIUS`].
+    pub fn debug_circle_segments(&self) -> usize {
+        self.debug_circle_segments
+    }
+    pub fn set_debug_circle_segments(&mut self, segments: usize) {
+        self.debug_circle_segments = segments;
+    }
+
+    /// Drawing commands of the whole scene: walls followed by items.
+    pub fn render_commands(&self, mode: DrawMode) -> impl Iterator<Item = RenderCommand> + '_ {
+        self.walls_commands(mode).into_iter().chain(
+            self.items
+                .iter()
+                .flat_map(move |item| item.render_commands(mode, self.debug_circle_segments)),
+        )
+    }
+
+    /// Drawing commands of the whole scene with items at [interpolated poses](Item::interpolated_pose).
+    pub fn render_commands_interpolated(
+        &self,
+        mode: DrawMode,
+        alpha: f32,
+    ) -> impl Iterator<Item = RenderCommand> + '_ {
+        self.walls_commands(mode)
+            .into_iter()
+            .chain(self.items.iter().flat_map(move |item| {
+                item.render_commands_interpolated(mode, self.debug_circle_segments, alpha)
+            }))
+    }
+
+    /// Screen size in pixels of a unit of world half-size, used to keep line widths constant on screen.
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+    /// Values that are not positive and finite are ignored.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        if scale > 0.0 && scale.is_finite() {
+            self.render_scale = scale;
+        }
+    }
+    /// Thickness of debug wall outline in world units, constant in pixels at any render scale.
+    pub fn wall_thickness(&self) -> f32 {
+        self.theme.wall_thickness_factor * BORDERX * RENDER_SCALE / self.render_scale
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+    /// Color to clear the screen with.
+    pub fn background(&self, mode: DrawMode) -> Rgb<f32> {
+        match mode {
+            DrawMode::Normal | DrawMode::LayerView => self.theme.background,
+            DrawMode::Debug => BLACK,
+        }
+    }
+
+    fn walls_commands(&self, mode: DrawMode) -> Vec<RenderCommand> {
+        let wall_size = self.wall_size();
+        let walls = match mode {
+            DrawMode::Normal | DrawMode::LayerView => std::iter::once(RenderCommand::Rect {
+                min: -wall_size,
+                max: wall_size,
+                color: self.theme.wall,
+                order: WALLS_ORDER,
+            })
+            .chain(self.grid_commands())
+            .collect(),
+            DrawMode::Debug => outline(
+                &[
+                    Vec2::new(-wall_size.x, -wall_size.y),
+                    Vec2::new(wall_size.x, -wall_size.y),
+                    Vec2::new(wall_size.x, wall_size.y),
+                    Vec2::new(-wall_size.x, wall_size.y),
+                ],
+                self.wall_thickness(),
+                self.theme.wall,
+                WALLS_ORDER,
+            )
+            .collect(),
+        };
+        walls
+    }
+
+    /// Lines of [`BackgroundStyle::Grid`] within the walls.
+    fn grid_commands(&self) -> Vec<RenderCommand> {
+        let BackgroundStyle::Grid { spacing, color } = self.theme.background_style else {
+            return Vec::new();
+        };
+        if !(spacing > 0.0 && spacing.is_finite()) {
+            return Vec::new();
+        }
+        let min_spacing = MIN_GRID_PIXELS / self.render_scale;
+        let spacing = if spacing < min_spacing {
+            spacing * (min_spacing / spacing).ceil()
+        } else {
+            spacing
+        };
+        let wall_size = self.wall_size();
+        let thickness = 0.5 * self.wall_thickness();
+        let line = |a, b| RenderCommand::Line {
+            a,
+            b,
+            thickness,
+            color,
+            order: GRID_ORDER,
+        };
+        // Lines go through the center, so the grid stays in place when the world is resized
+        let (nx, ny) = (
+            (wall_size.x / spacing) as i32,
+            (wall_size.y / spacing) as i32,
+        );
+        let vertical = (-nx..=nx).map(|k| {
+            let x = k as f32 * spacing;
+            line(Vec2::new(x, -wall_size.y), Vec2::new(x, wall_size.y))
+        });
+        let horizontal = (-ny..=ny).map(|k| {
+            let y = k as f32 * spacing;
+            line(Vec2::new(-wall_size.x, y), Vec2::new(wall_size.x, y))
+        });
+        vertical.chain(horizontal).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phy::Rk4;
+
+    /// Grid lines as `(a, b)` endpoints.
+    fn grid_lines(world: &World<Rk4>) -> Vec<(Vec2, Vec2)> {
+        world
+            .render_commands(DrawMode::Normal)
+            .filter_map(|command| match command {
+                RenderCommand::Line { a, b, order, .. } if order == GRID_ORDER => Some((a, b)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grid_line_count() {
+        let mut world = World::<Rk4>::new(Vec2::new(1.05, 0.55));
+        assert!(grid_lines(&world).is_empty());
+
+        world.set_theme(Theme {
+            background_style: BackgroundStyle::Grid {
+                spacing: 0.3,
+                color: Rgb::new(0.5, 0.5, 0.5),
+            },
+            ..Theme::default()
+        });
+        let lines = grid_lines(&world);
+        // Lines at 0, ±0.3, ±0.6, ±0.9 across x and 0, ±0.3 across y
+        let vertical = lines.iter().filter(|(a, b)| a.x == b.x).count();
+        let horizontal = lines.iter().filter(|(a, b)| a.y == b.y).count();
+        assert_eq!((vertical, horizontal), (7, 3));
+
+        let wall_size = world.wall_size();
+        for (a, b) in lines {
+            assert!(a.abs().cmple(wall_size).all() && b.abs().cmple(wall_size).all());
+        }
+    }
+
+    #[test]
+    fn dense_grid_is_thinned_out() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let grid = |spacing| Theme {
+            background_style: BackgroundStyle::Grid {
+                spacing,
+                color: Rgb::new(0.5, 0.5, 0.5),
+            },
+            ..Theme::default()
+        };
+        for spacing in [f32::NAN, f32::INFINITY, -1.0] {
+            world.set_theme(grid(spacing));
+            assert!(grid_lines(&world).is_empty());
+        }
+
+        world.set_theme(grid(1e-7));
+        let lines = grid_lines(&world);
+        // Both directions, with a margin for rounding
+        let max_lines = 2.0 * (2.0 * world.wall_size().x * RENDER_SCALE / MIN_GRID_PIXELS + 3.0);
+        assert!(!lines.is_empty());
+        assert!(lines.len() as f32 <= max_lines, "{} lines", lines.len());
+    }
+
+    #[test]
+    fn invalid_render_scale_is_ignored() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_render_scale(2.0 * RENDER_SCALE);
+        for scale in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            world.set_render_scale(scale);
+            assert_eq!(world.render_scale(), 2.0 * RENDER_SCALE);
+            assert!(world.wall_thickness().is_finite() && world.wall_thickness() > 0.0);
+        }
+    }
+
+    /// Transforms of textured quads of the item.
+    fn quads(item: &Item<Rk4>, mode: DrawMode) -> Vec<(Affine2, Rgb<f32>)> {
+        item.render_commands(mode, CIRCLE_SEGMENTS)
+            .into_iter()
+            .filter_map(|command| match command {
+                RenderCommand::Quad {
+                    transform, color, ..
+                } => Some((transform, color)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn visual_scale_applies_to_quad_only() {
+        let pos = Vec2::new(0.2, 0.1);
+        let mut item = Item::<Rk4>::new(Shape::Circle { radius: 0.1 }, pos, WHITE);
+        item.visual_scale = 1.5;
+        let [(transform, _)] = quads(&item, DrawMode::Normal)[..] else {
+            panic!("single quad expected");
+        };
+        let edge = transform.transform_point2(Vec2::new(1.0, 0.0));
+        assert!(edge.distance(pos + Vec2::new(0.15, 0.0)) < 1e-6);
+
+        // Debug outline keeps the physical radius
+        for command in item.render_commands(DrawMode::Debug, CIRCLE_SEGMENTS) {
+            if let RenderCommand::Line { a, .. } = command {
+                assert!((a.distance(pos) - 0.1).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn layer_view_tints_by_layer() {
+        assert_eq!(layer_color(0b10), layer_color(0b10));
+        assert_ne!(layer_color(0b01), layer_color(0b10));
+
+        let mut item = Item::<Rk4>::new(Shape::Circle { radius: 0.1 }, Vec2::ZERO, WHITE);
+        item.layer = 0b10;
+        let [(_, color)] = quads(&item, DrawMode::LayerView)[..] else {
+            panic!("single quad expected");
+        };
+        assert_eq!(color, layer_color(0b10));
+        let [(_, color)] = quads(&item, DrawMode::Normal)[..] else {
+            panic!("single quad expected");
+        };
+        assert_eq!(color, WHITE);
+    }
+
+    #[test]
+    fn transform_maps_unit_quad_onto_item() {
+        let pos = Vec2::new(0.3, -0.2);
+        let mut item = Item::<Rk4>::new(
+            Shape::Rectangle {
+                size: Vec2::new(0.2, 0.1),
+            },
+            pos,
+            WHITE,
+        );
+        item.set_pose(pos, phy::Rot2::from_angle(0.5 * std::f32::consts::PI));
+        let transform = item.transform();
+        assert!(transform.transform_point2(Vec2::ZERO).distance(pos) < 1e-6);
+        // Quarter turn maps local +x onto +y
+        let corner = transform.transform_point2(Vec2::ONE);
+        assert!(corner.distance(pos + Vec2::new(-0.1, 0.2)) < 1e-6);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{BoundaryMode, ContactTarget, WallSide, World};
+use glam::Vec2;
+use phy::Solver;
+
+/// Margin outside the walls that an item must cross entirely to be considered escaped.
+const ESCAPE_MARGIN: f32 = 0.1;
+
+/// Receiver of world events.
+///
+/// All methods do nothing by default.
+pub trait Listener {
+    /// `index`-th item at `pos` has entirely left the walls, e.g. through an open side.
+    ///
+    /// Called after each step while the item stays outside.
+    fn on_item_escaped(&mut self, index: usize, pos: Vec2) {
+        let _ = (index, pos);
+    }
+
+    /// `index`-th item touches `other` at `point` approaching it with `impact_speed`.
+    ///
+    /// Called after each step for every contact, resting contacts have nearly zero impact speed.
+    fn on_collision(&mut self, index: usize, other: ContactTarget, point: Vec2, impact_speed: f32) {
+        let _ = (index, other, point, impact_speed);
+    }
+}
+
+impl<S: Solver> World<S> {
+    pub fn boundary(&self, side: WallSide) -> BoundaryMode {
+        self.boundaries[side as usize]
+    }
+    pub fn set_boundary(&mut self, side: WallSide, mode: BoundaryMode) {
+        self.boundaries[side as usize] = mode;
+    }
+
+    pub fn set_listener(&mut self, listener: Option<Box<dyn Listener>>) {
+        self.listener = listener;
+    }
+    pub fn take_listener(&mut self) -> Option<Box<dyn Listener>> {
+        self.listener.take()
+    }
+
+    pub fn remove_escaped(&self) -> bool {
+        self.remove_escaped
+    }
+    /// Automatically remove escaped items after notifying the listener.
+    pub fn set_remove_escaped(&mut self, remove: bool) {
+        self.remove_escaped = remove;
+    }
+
+    /// Call `f` with the listener if it is set.
+    pub(crate) fn notify(&mut self, f: impl FnOnce(&mut dyn Listener)) {
+        if let Some(mut listener) = self.listener.take() {
+            f(listener.as_mut());
+            self.listener = Some(listener);
+        }
+    }
+
+    /// Report all contacts to the listener.
+    pub(crate) fn handle_collisions(&mut self) {
+        if self.listener.is_none() {
+            return;
+        }
+        let impacts = self.impacts();
+        self.notify(|listener| {
+            for (i, other, point, speed) in impacts {
+                listener.on_collision(i, other, point, speed);
+            }
+        });
+    }
+
+    /// Report and optionally remove items that are entirely outside the walls.
+    pub(crate) fn handle_escaped(&mut self) {
+        let bound = self.wall_size() + Vec2::splat(ESCAPE_MARGIN);
+        let escaped = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| {
+                let (min, max) = item.aabb();
+                min.cmpgt(bound).any() || max.cmplt(-bound).any()
+            })
+            .map(|(i, item)| (i, *item.pos))
+            .collect::<Vec<_>>();
+        if escaped.is_empty() {
+            return;
+        }
+        self.notify(|listener| {
+            for &(i, pos) in &escaped {
+                listener.on_item_escaped(i, pos);
+            }
+        });
+        if self.remove_escaped {
+            for &(i, _) in escaped.iter().rev() {
+                self.remove_item(i);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Item, Shape};
+    use phy::Rk4;
+    use rgb::Rgb;
+    use std::{cell::RefCell, rc::Rc};
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[derive(Default)]
+    struct Escapes(Rc<RefCell<Vec<(usize, Vec2)>>>);
+
+    impl Listener for Escapes {
+        fn on_item_escaped(&mut self, index: usize, pos: Vec2) {
+            self.0.borrow_mut().push((index, pos));
+        }
+    }
+
+    #[test]
+    fn ball_escapes_through_open_floor() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_boundary(WallSide::Bottom, BoundaryMode::Open);
+        world.set_remove_escaped(true);
+        let escapes = Escapes::default();
+        let events = escapes.0.clone();
+        world.set_listener(Some(Box::new(escapes)));
+        let ball = |x| {
+            Item::new(
+                Shape::Circle { radius: 0.05 },
+                Vec2::new(x, 0.0),
+                Rgb::new(1.0, 1.0, 1.0),
+            )
+        };
+        world.insert_item(ball(-0.5));
+        world.insert_item(ball(0.5));
+
+        for _ in 0..120 {
+            world.advance(&mut Rk4, DT);
+        }
+        // Both items fall out at the same step and are reported once
+        let events = events.borrow();
+        assert_eq!(events.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [0, 1]);
+        for (_, pos) in events.iter() {
+            assert!(pos.y > world.wall_size().y + ESCAPE_MARGIN);
+        }
+        assert_eq!(world.n_items(), 0);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    World,
+    physics::{Actor, apply_body_force},
+};
+use glam::Vec2;
+use phy::Solver;
+
+/// Minimal distance used in inverse-square falloff to avoid singularity.
+const FALLOFF_SOFTENING: f32 = 0.1;
+
+/// Dependency of force magnitude on distance from its source.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Falloff {
+    /// The same everywhere.
+    Constant,
+    /// Decreases linearly from the source and vanishes at `radius`.
+    Linear { radius: f32 },
+    /// Inverse-square law softened near the source.
+    InverseSquare,
+    /// Smooth bell of width `sigma`.
+    Gaussian { sigma: f32 },
+}
+
+impl Falloff {
+    /// Factor of force at `distance` from the source, equals to one at the source.
+    pub fn factor(&self, distance: f32) -> f32 {
+        match *self {
+            Falloff::Constant => 1.0,
+            Falloff::Linear { radius } => {
+                if radius > 0.0 {
+                    (1.0 - distance / radius).max(0.0)
+                } else {
+                    0.0
+                }
+            }
+            Falloff::InverseSquare => {
+                FALLOFF_SOFTENING * FALLOFF_SOFTENING
+                    / (distance * distance + FALLOFF_SOFTENING * FALLOFF_SOFTENING)
+            }
+            Falloff::Gaussian { sigma } => {
+                if sigma > 0.0 {
+                    (-0.5 * (distance / sigma).powi(2)).exp()
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Point pulling all items towards itself, or pushing them away if `strength` is negative.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Attractor {
+    pub pos: Vec2,
+    /// Acceleration at the source.
+    pub strength: f32,
+    pub falloff: Falloff,
+}
+
+impl<S: Solver> World<S> {
+    /// Push all dynamic items away from `center` by impulse of `magnitude` scaled by `falloff`.
+    pub fn apply_explosion(&mut self, center: Vec2, magnitude: f32, falloff: Falloff) {
+        for item in &mut self.items {
+            if !item.is_dynamic() {
+                continue;
+            }
+            let rel_pos = *item.pos - center;
+            let impulse =
+                magnitude * falloff.factor(rel_pos.length()) * rel_pos.normalize_or_zero();
+            let pos = *item.pos;
+            item.apply_impulse(impulse, pos);
+        }
+    }
+
+    pub fn add_attractor(&mut self, pos: Vec2, strength: f32, falloff: Falloff) {
+        self.attractors.push(Attractor {
+            pos,
+            strength,
+            falloff,
+        });
+    }
+    pub fn attractors(&self) -> &[Attractor] {
+        &self.attractors
+    }
+    pub fn clear_attractors(&mut self) {
+        self.attractors.clear();
+    }
+
+    /// Apply forces of all attractors to all items.
+    pub(crate) fn apply_attractors(&mut self, actor: &mut impl Actor<S>) {
+        for attractor in &self.attractors {
+            for (i, item) in self.items.iter_mut().enumerate() {
+                let rel_pos = attractor.pos - *item.pos;
+                let force = attractor.strength
+                    * item.mass
+                    * attractor.falloff.factor(rel_pos.length())
+                    * rel_pos.normalize_or_zero();
+                actor.interaction(i, None);
+                let pos = *item.pos;
+                apply_body_force(actor, &mut item.body, pos, force);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Item, Shape};
+    use phy::Rk4;
+    use rgb::Rgb;
+
+    const SIGMA: f32 = 0.2;
+
+    /// World with two weightless balls at 1σ and 2σ from the origin.
+    fn two_balls() -> World<Rk4> {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for x in [SIGMA, 2.0 * SIGMA] {
+            let mut item = Item::new(
+                Shape::Circle { radius: 0.05 },
+                Vec2::new(x, 0.0),
+                Rgb::new(1.0, 1.0, 1.0),
+            );
+            item.gravity_scale = 0.0;
+            world.insert_item(item);
+        }
+        world
+    }
+
+    #[test]
+    fn gaussian_ratio() {
+        let expected = 1.5_f32.exp();
+        let falloff = Falloff::Gaussian { sigma: SIGMA };
+        let ratio = falloff.factor(SIGMA) / falloff.factor(2.0 * SIGMA);
+        assert!((ratio - expected).abs() < 1e-4 * expected);
+
+        let mut world = two_balls();
+        world.apply_explosion(Vec2::ZERO, 1.0, falloff);
+        let speeds = world.items().map(|item| item.vel.x).collect::<Vec<_>>();
+        assert!((speeds[0] / speeds[1] - expected).abs() < 1e-4 * expected);
+
+        let mut world = two_balls();
+        world.add_attractor(Vec2::ZERO, 1.0, falloff);
+        let mut forces = [Vec2::ZERO; 2];
+        for record in world.collect_contacts() {
+            forces[record.a] += record.force;
+        }
+        assert!((forces[0].x / forces[1].x - expected).abs() < 1e-4 * expected);
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{Item, Shape, World, color_from_seed, physics::Actor, reindex};
+use glam::Vec2;
+use phy::Solver;
+use std::{f32::consts::PI, ops::Range};
+
+/// Default stiffness of joint springs.
+pub const JOINT_STIFFNESS: f32 = 100.0;
+/// Damping of soft body joints.
+const SOFT_BODY_DAMPING: f32 = 1.0;
+
+/// Identifier of a joint added to the world.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct JointId(pub u64);
+
+/// What the second end of a joint is attached to.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum JointTarget {
+    /// Fixed point in world space.
+    Point(Vec2),
+    /// Point of the item given in its body space.
+    Item(usize, Vec2),
+}
+
+/// Spring keeping a point of an item at fixed distance from the target.
+///
+/// Joint of zero length is a pin, the item can freely rotate about it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Joint {
+    pub item: usize,
+    /// Attachment point in body space of the item.
+    pub local: Vec2,
+    pub target: JointTarget,
+    /// Rest length.
+    pub length: f32,
+    pub stiffness: f32,
+    /// Damping of relative velocity along the joint.
+    pub damping: f32,
+}
+
+impl Joint {
+    /// Pin `local` point of `item` to `anchor` point in world space.
+    pub fn pin(item: usize, local: Vec2, anchor: Vec2) -> Self {
+        Self {
+            item,
+            local,
+            target: JointTarget::Point(anchor),
+            length: 0.0,
+            stiffness: JOINT_STIFFNESS,
+            damping: 0.0,
+        }
+    }
+
+    /// Keep points of two items at `length` from each other.
+    pub fn distance(a: usize, local_a: Vec2, b: usize, local_b: Vec2, length: f32) -> Self {
+        Self {
+            item: a,
+            local: local_a,
+            target: JointTarget::Item(b, local_b),
+            length,
+            stiffness: JOINT_STIFFNESS,
+            damping: 0.0,
+        }
+    }
+
+    /// Second item the joint is attached to.
+    fn other(&self) -> Option<usize> {
+        match self.target {
+            JointTarget::Point(_) => None,
+            JointTarget::Item(j, _) => Some(j),
+        }
+    }
+    /// Items the joint is attached to.
+    fn items(&self) -> impl Iterator<Item = usize> {
+        std::iter::once(self.item).chain(self.other())
+    }
+}
+
+/// Particles and joints of a soft body.
+#[derive(Clone, Debug)]
+pub struct SoftBody {
+    /// Indices of particles, the first one is at the center.
+    ///
+    /// They are valid until any item is removed.
+    pub items: Range<usize>,
+    pub joints: Vec<JointId>,
+}
+
+impl<S: Solver> World<S> {
+    /// Add a deformable blob of `radius` at `center` made of a ring of `particle_count` small circles.
+    ///
+    /// Neighbor particles of the ring are connected by distance joints, and each of them is connected to
+    /// the central particle by a spoke. Joints have the given `stiffness`.
+    ///
+    /// Room for all particles is made at once according to the [`EvictionPolicy`](crate::EvictionPolicy),
+    /// so evictions don't shift them. Soft body larger than the [item limit](Self::max_items) is inserted anyway.
+    pub fn add_soft_body(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        particle_count: usize,
+        stiffness: f32,
+    ) -> SoftBody {
+        let n = particle_count.max(3);
+        let particle_radius = 0.4 * 2.0 * radius * (PI / n as f32).sin();
+        let color = color_from_seed(self.items.len() as u64);
+        let positions = std::iter::once(center)
+            .chain(
+                (0..n).map(|k| center + radius * Vec2::from_angle(2.0 * PI * k as f32 / n as f32)),
+            )
+            .collect::<Vec<_>>();
+        self.make_room(positions.len());
+        let start = self.items.len();
+        for pos in positions.iter().copied() {
+            let shape = Shape::Circle {
+                radius: particle_radius,
+            };
+            self.items.push(Item::new(shape, pos, color));
+        }
+        let items = start..self.items.len();
+
+        let mut joints = Vec::new();
+        for k in 1..=n {
+            let next = k % n + 1;
+            for other in [0, next] {
+                let length = positions[k].distance(positions[other]);
+                let joint = Joint {
+                    stiffness,
+                    damping: SOFT_BODY_DAMPING,
+                    ..Joint::distance(start + k, Vec2::ZERO, start + other, Vec2::ZERO, length)
+                };
+                joints.extend(self.add_joint(joint));
+            }
+        }
+        SoftBody { items, joints }
+    }
+
+    /// Add joint, returns `None` if it refers to non-existent items or connects an item to itself.
+    pub fn add_joint(&mut self, joint: Joint) -> Option<JointId> {
+        if joint.items().any(|i| i >= self.items.len()) || joint.other() == Some(joint.item) {
+            return None;
+        }
+        let id = JointId(self.next_joint_id);
+        self.next_joint_id += 1;
+        self.joints.push((id, joint));
+        Some(id)
+    }
+    /// Remove the joint, returns `None` if there is no such joint.
+    pub fn remove_joint(&mut self, id: JointId) -> Option<Joint> {
+        let index = self.joints.iter().position(|(i, _)| *i == id)?;
+        Some(self.joints.remove(index).1)
+    }
+    pub fn joints(&self) -> impl ExactSizeIterator<Item = (JointId, &Joint)> + '_ {
+        self.joints.iter().map(|(id, joint)| (*id, joint))
+    }
+
+    /// Remove joints attached to `i`-th item.
+    pub(crate) fn remove_joints_of(&mut self, i: usize) {
+        self.joints
+            .retain(|(_, joint)| joint.items().all(|j| j != i));
+    }
+
+    /// Update joints after `i`-th item was removed, joints of the removed item are dropped.
+    pub(crate) fn reindex_joints(&mut self, i: usize) {
+        self.joints = std::mem::take(&mut self.joints)
+            .into_iter()
+            .filter_map(|(id, joint)| {
+                let target = match joint.target {
+                    JointTarget::Item(j, local) => JointTarget::Item(reindex(j, i)?, local),
+                    point => point,
+                };
+                Some((
+                    id,
+                    Joint {
+                        item: reindex(joint.item, i)?,
+                        target,
+                        ..joint
+                    },
+                ))
+            })
+            .collect();
+    }
+
+    /// Apply spring forces of all joints.
+    pub(crate) fn apply_joints(&mut self, actor: &mut impl Actor<S>) {
+        for (_, joint) in &self.joints {
+            let item = &self.items[joint.item];
+            let a_pos = *item.pos + item.rot.transform(joint.local);
+            let a_vel = item.vel_at(a_pos);
+            let (b_pos, b_vel) = match joint.target {
+                JointTarget::Point(anchor) => (anchor, Vec2::ZERO),
+                JointTarget::Item(j, local) => {
+                    let other = &self.items[j];
+                    let pos = *other.pos + other.rot.transform(local);
+                    (pos, other.vel_at(pos))
+                }
+            };
+            let rel_pos = b_pos - a_pos;
+            let dir = rel_pos.normalize_or_zero();
+            let elast_f = joint.stiffness * (rel_pos - joint.length * dir);
+            let damp_f = joint.damping * (b_vel - a_vel).dot(dir) * dir;
+            let force = elast_f + damp_f;
+
+            let other = joint.other();
+            actor.interaction(joint.item, other);
+            actor.apply(&mut self.items[joint.item].body, a_pos, force);
+            if let Some(j) = other {
+                actor.interaction(j, Some(joint.item));
+                actor.apply(&mut self.items[j].body, b_pos, -force);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EvictionPolicy;
+    use phy::Rk4;
+    use rgb::Rgb;
+
+    /// Vertical extent of particle centers.
+    fn height(world: &World<Rk4>, body: &SoftBody) -> f32 {
+        let ys = body.items.clone().map(|i| world.item(i).unwrap().pos.y);
+        let (min, max) = ys.fold((f32::MAX, f32::MIN), |(a, b), y| (a.min(y), b.max(y)));
+        max - min
+    }
+
+    #[test]
+    fn soft_body_compresses_and_rebounds() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let body = world.add_soft_body(Vec2::new(0.0, 0.3), 0.2, 12, 50.0);
+        let initial = height(&world, &body);
+
+        let (mut min, mut rebound) = (initial, 0.0f32);
+        for _ in 0..180 {
+            world.advance(&mut Rk4, 1.0 / 60.0);
+            let h = height(&world, &body);
+            if h < min {
+                (min, rebound) = (h, h);
+            }
+            rebound = rebound.max(h);
+        }
+        assert!(min < 0.95 * initial, "{min} >= {initial}");
+        assert!(rebound > min + 0.25 * (initial - min), "{rebound} <= {min}");
+    }
+
+    #[test]
+    fn soft_body_survives_eviction() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_max_items(Some(10));
+        world.set_eviction_policy(EvictionPolicy::Oldest);
+        for k in 0..10 {
+            let pos = Vec2::new(-0.8 + 0.15 * k as f32, 0.8);
+            let item = Item::new(Shape::Circle { radius: 0.05 }, pos, Rgb::new(1.0, 1.0, 1.0));
+            world.insert_item(item);
+        }
+        let body = world.add_soft_body(Vec2::ZERO, 0.2, 6, JOINT_STIFFNESS);
+
+        assert_eq!(body.items, 3..10);
+        assert_eq!(world.n_items(), 10);
+        assert_eq!(world.joints().len(), body.joints.len());
+        for (_, joint) in world.joints() {
+            assert!(joint.items().all(|i| body.items.contains(&i)));
+        }
+    }
+}
@@ -1,34 +1,68 @@
+mod draw;
+mod events;
+mod forces;
+mod joint;
 mod physics;
+#[cfg(feature = "profiling")]
+mod profile;
+#[cfg(feature = "render")]
+mod render;
+#[cfg(feature = "json")]
+mod scene;
+mod scenes;
+mod snapshot;
+mod spawn;
+mod view;
+mod weld;
+
+pub use crate::events::Listener;
+pub use crate::forces::{Attractor, Falloff};
+pub use crate::joint::{JOINT_STIFFNESS, Joint, JointId, JointTarget, SoftBody};
+pub use crate::physics::{
+    Actor, AdaptiveStats, Body, BodyKind, BoundaryMode, Collider, CompoundPart, Contact,
+    ContactInfo, ContactModel, ContactRecord, ContactTarget, DRAG_TIME, DerivActor, DragModel,
+    DragStyle, FluidRegion, FrictionModel, ITEM_STATE_LEN, ITERATION_CORRECTION, JAM_OVERLAP,
+    MAX_SUBSTEP, MAX_SUBSTEPS, Material, OverlapRegion, PhysicsConfig, Shape, WALL_OFFSET,
+    WallSide, detect_collision,
+};
+pub use crate::snapshot::{BodyState, PhysicsSnapshot};
+pub use crate::spawn::{Spawner, SpawnerId};
+pub use crate::view::{FOLLOW_SMOOTHING, WorldView, ZOOM_RANGE};
+
+pub use crate::draw::{
+    BackgroundStyle, CIRCLE_SEGMENTS, DrawMode, RENDER_SCALE, RenderCommand, SEGMENTS_RADIUS,
+    Theme, layer_color,
+};
+#[cfg(feature = "profiling")]
+pub use crate::profile::StepTimings;
+#[cfg(feature = "render")]
+pub use crate::render::{DrawActor, LoadError, MissingTexturePolicy, RenderError, TextureStorage};
+#[cfg(feature = "json")]
+pub use crate::scene::SceneError;
 
-use crate::physics::{Actor, Body, Shape, WALL_OFFSET};
 use derive_more::derive::{Deref, DerefMut};
-use glam::{Affine2, Vec2, Vec4, Vec4Swizzles};
+use glam::Vec2;
 use hsl::HSL;
 use phy::{Rot2, Solver, Var};
-use rand::Rng;
+use rand::{Rng, SeedableRng, rngs::SmallRng};
 use rand_distr::Uniform;
 use rgb::Rgb;
-use wgame::{
-    Library,
-    fs::Path,
-    gfx::{
-        Scene,
-        types::{Color, color},
-    },
-    image::Image,
-    prelude::*,
-    texture::{Texture, TextureSettings},
-};
+use std::cmp::Ordering;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
-pub enum DrawMode {
-    #[default]
-    Normal,
-    Debug,
-}
+/// Seed of the world RNG.
+pub const DEFAULT_SEED: u64 = 0xdeadbeef;
 
-/// Drawing border thickness factor
-const BORDERX: f32 = 1.0 / 24.0;
+/// Rotation snapping step
+pub const ANGLE_SNAP: f32 = std::f32::consts::PI / 12.0;
+
+/// Identifier of a texture in the texture storage.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TextureId(pub usize);
+
+impl TextureId {
+    pub const BALL: Self = Self(0);
+    pub const NOISE: Self = Self(1);
+}
 
 #[derive(Clone, Deref, DerefMut)]
 pub struct Item<S: Solver> {
@@ -37,282 +71,1091 @@ pub struct Item<S: Solver> {
     pub body: Body<S>,
     pub shape: Shape,
 
-    pub texture: Texture,
+    /// Texture multiplied by color, plain color is used if absent.
+    pub texture: Option<TextureId>,
     pub color: Rgb<f32>,
+    /// Scale of the textured quad relative to the shape, used to fit the opaque region
+    /// of a texture with margins to the collision shape. Doesn't affect physics.
+    pub visual_scale: f32,
+
+    /// Arbitrary user data, preserved by the world and never interpreted.
+    pub tag: u64,
+    /// Number of walls and items touched at the end of the last step.
+    pub contacts: usize,
+    /// Items with greater order are drawn above, see [`Shape::default_order`].
+    pub order: i32,
 }
 
 impl<S: Solver> Item<S> {
-    pub fn draw(&self, lib: &Library, scene: &mut Scene, mode: DrawMode) {
-        let (size, order) = match &self.shape {
-            Shape::Circle { radius } => (Vec2::splat(*radius), 1),
-            Shape::Rectangle { size } => (*size, 0),
-        };
-        match mode {
-            DrawMode::Normal => {
-                scene.add(
-                    &lib.shapes()
-                        .unit_quad()
-                        .transform(Affine2::from_scale_angle_translation(
-                            size,
-                            self.rot.angle(),
-                            *self.pos,
-                        ))
-                        .fill_texture(&self.texture)
-                        .multiply_color(self.color)
-                        .order(order),
-                );
-            }
-            DrawMode::Debug => match &self.shape {
-                Shape::Circle { radius } => {
-                    /*
-                    draw_circle_lines(
-                        self.pos.x,
-                        self.pos.y,
-                        *radius,
-                        BORDERX * radius,
-                        self.color,
-                    ),
-                    */
-                }
-                Shape::Rectangle { .. } => {
-                    // Draw later
-                }
+    /// Item of `shape` resting at `pos`, its mass and moment of inertia are derived from shape radius.
+    pub fn new(shape: Shape, pos: Vec2, color: Rgb<f32>) -> Self {
+        let radius = shape.radius();
+        let mass = physics::MASF * radius;
+        Item {
+            body: Body {
+                mass,
+                pos: Var::new(pos),
+                inm: shape.moment_of_inertia(mass),
+                prev_pos: pos,
+                ..Body::default()
             },
+            color,
+            texture: match &shape {
+                Shape::Circle { .. } => Some(TextureId::BALL),
+                _ => Some(TextureId::NOISE),
+            },
+            visual_scale: 1.0,
+            order: shape.default_order(),
+            shape,
+            tag: 0,
+            contacts: 0,
         }
-        if let Shape::Rectangle { .. } = &self.shape {
-            /*
-            draw_rectangle_lines_ex(
-                self.pos.x,
-                self.pos.y,
-                2.0 * size.x,
-                2.0 * size.y,
-                BORDERX * size.min_element(),
-                DrawRectangleParams {
-                    offset: Vec2::new(0.5, 0.5),
-                    rotation: self.rot.angle(),
-                    color: match mode {
-                        DrawMode::Normal => color::BLACK,
-                        DrawMode::Debug => self.color,
-                    },
-                },
-            );
-            */
-        }
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+    /// Replace shape keeping mass, moment of inertia is recomputed for the new shape.
+    ///
+    /// New shape may overlap neighbors, the overlap is resolved by contact forces and position correction on next steps.
+    pub fn set_shape(&mut self, shape: Shape) {
+        self.inm = shape.moment_of_inertia(self.mass);
+        self.shape = shape;
+    }
+
+    pub fn tag(&self) -> u64 {
+        self.tag
+    }
+    pub fn set_tag(&mut self, tag: u64) {
+        self.tag = tag;
+    }
+}
+
+/// Which item is removed when a new one is inserted into a full world.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum EvictionPolicy {
+    /// The earliest inserted item.
+    #[default]
+    Oldest,
+    /// Random item chosen using the world RNG.
+    Random,
+    /// Item with the least kinetic energy.
+    SlowestMoving,
+}
+
+/// New value of the `index` after the item at `removed` index was removed.
+///
+/// Returns `None` if the `index` refers to the removed item.
+fn reindex(index: usize, removed: usize) -> Option<usize> {
+    match index.cmp(&removed) {
+        Ordering::Less => Some(index),
+        Ordering::Equal => None,
+        Ordering::Greater => Some(index - 1),
     }
 }
 
+/// Box of items simulated and drawn together.
+///
+/// Items, joints, attractors and spawners are stored in insertion order and processed in it,
+/// so the same inputs with the same seed reproduce the simulation exactly.
 pub struct World<S: Solver> {
     /// Half of world sides
     size: Vec2,
     items: Vec<Item<S>>,
     drag: Option<(usize, Vec2, Vec2)>,
+
+    max_penetration: f32,
+    total_overlap: f32,
+    position_correction: f32,
+    /// Number of position correction passes per sub-step.
+    solver_iterations: usize,
+    edit_mode: bool,
+    drag_model: DragModel,
+    contact_model: ContactModel,
+    config: PhysicsConfig,
+    /// Uniform acceleration field.
+    field: Vec2,
+    wall_material: Material,
+    /// Thickness of repulsive layer along walls.
+    wall_cushion: f32,
+    max_angular_speed: Option<f32>,
+    fluids: Vec<FluidRegion>,
+    attractors: Vec<Attractor>,
+    time_scale: f32,
+
+    /// Number of simulation steps performed since creation or last clear.
+    step_count: u64,
+    /// Simulated time since creation or last clear.
+    elapsed: f64,
+
+    /// Grid cell used to snap placed items.
+    grid_snap: Option<f32>,
+    /// Snap rotation of placed items to [`ANGLE_SNAP`] steps.
+    angle_snap: bool,
+    /// Extra distance around items at which they are picked.
+    grab_tolerance: f32,
+    clamp_drag_to_bounds: bool,
+    drag_style: DragStyle,
+    /// Item tracked by the camera.
+    followed: Option<usize>,
+
+    debug_circle_segments: usize,
+    theme: Theme,
+    render_scale: f32,
+
+    /// Source of randomness for items created by the world itself.
+    rng: SmallRng,
+    /// Number of items created by the world, used as a color seed.
+    n_spawned: u64,
+    spawners: Vec<(SpawnerId, Spawner)>,
+    next_spawner_id: u64,
+    spawn_cap: Option<usize>,
+    /// Number of items to be poured and simulated time of the next one.
+    pour_remaining: usize,
+    next_pour: f64,
+
+    /// Maximum number of items, exceeding items are evicted according to `eviction`.
+    max_items: Option<usize>,
+    eviction: EvictionPolicy,
+
+    boundaries: [BoundaryMode; 4],
+    listener: Option<Box<dyn Listener>>,
+    /// Remove items which left the walls.
+    remove_escaped: bool,
+
+    joints: Vec<(JointId, Joint)>,
+    next_joint_id: u64,
+
+    /// Timings of the current advance.
+    #[cfg(feature = "profiling")]
+    timings: StepTimings,
+    #[cfg(feature = "profiling")]
+    timing_stats: StepTimings,
+}
+
+/// Empty unit-sized world.
+impl<S: Solver> Default for World<S> {
+    fn default() -> Self {
+        Self::new(Vec2::ONE)
+    }
 }
 
 impl<S: Solver> World<S> {
+    /// Empty world of `size` half-sides.
+    ///
+    /// The size may be provisional, e.g. before the window size is known,
+    /// the world is fully usable right away and can be [resized](Self::resize) later.
+    /// Negative size is clamped to zero, such world is [degenerate](Self::is_degenerate).
     pub fn new(size: Vec2) -> Self {
         Self {
-            size,
+            size: size.max(Vec2::ZERO),
             items: Vec::new(),
             drag: None,
+            max_penetration: 0.0,
+            total_overlap: 0.0,
+            position_correction: 0.0,
+            solver_iterations: 1,
+            edit_mode: false,
+            drag_model: DragModel::default(),
+            contact_model: ContactModel::default(),
+            config: PhysicsConfig::default(),
+            field: Vec2::ZERO,
+            wall_material: Material::default(),
+            wall_cushion: 0.0,
+            max_angular_speed: None,
+            fluids: Vec::new(),
+            attractors: Vec::new(),
+            time_scale: 1.0,
+            step_count: 0,
+            elapsed: 0.0,
+            grid_snap: None,
+            angle_snap: false,
+            grab_tolerance: 0.0,
+            clamp_drag_to_bounds: false,
+            drag_style: DragStyle::default(),
+            followed: None,
+            debug_circle_segments: draw::CIRCLE_SEGMENTS,
+            theme: Theme::default(),
+            render_scale: draw::RENDER_SCALE,
+            rng: SmallRng::seed_from_u64(DEFAULT_SEED),
+            n_spawned: 0,
+            spawners: Vec::new(),
+            next_spawner_id: 0,
+            spawn_cap: None,
+            pour_remaining: 0,
+            next_pour: 0.0,
+            max_items: None,
+            eviction: EvictionPolicy::default(),
+            boundaries: [BoundaryMode::Closed; 4],
+            listener: None,
+            remove_escaped: false,
+            joints: Vec::new(),
+            next_joint_id: 0,
+            #[cfg(feature = "profiling")]
+            timings: StepTimings::default(),
+            #[cfg(feature = "profiling")]
+            timing_stats: StepTimings::default(),
         }
     }
 
+    /// Empty world with space reserved for `capacity` items.
+    pub fn with_capacity(size: Vec2, capacity: usize) -> Self {
+        let mut world = Self::new(size);
+        world.items.reserve(capacity);
+        world
+    }
+
     pub fn size(&self) -> Vec2 {
         self.size
     }
 
+    /// Half of sides of the area enclosed by walls.
+    ///
+    /// Walls are inset by [`WALL_OFFSET`] regardless of world size and aspect ratio.
+    /// Both physics and drawing use this size.
+    pub fn wall_size(&self) -> Vec2 {
+        (self.size - Vec2::splat(WALL_OFFSET)).max(Vec2::ZERO)
+    }
+
+    /// Whether there is no room inside the walls, e.g. when the window is minimized.
+    ///
+    /// Simulation and spawning are paused in degenerate world.
+    pub fn is_degenerate(&self) -> bool {
+        self.wall_size().cmple(Vec2::ZERO).any()
+    }
+
+    /// Round `pos` to the nearest node of the grid with `cell` size.
+    pub fn snap_to_grid(pos: Vec2, cell: f32) -> Vec2 {
+        (pos / cell).round() * cell
+    }
+
+    pub fn grid_snap(&self) -> Option<f32> {
+        self.grid_snap
+    }
+    /// Snap positions of inserted and teleported items to the grid.
+    pub fn set_grid_snap(&mut self, cell: Option<f32>) {
+        self.grid_snap = cell.filter(|c| *c > 0.0);
+    }
+    pub fn angle_snap(&self) -> bool {
+        self.angle_snap
+    }
+    /// Snap angles of teleported items to [`ANGLE_SNAP`] steps.
+    pub fn set_angle_snap(&mut self, snap: bool) {
+        self.angle_snap = snap;
+    }
+
+    /// Position where item with `shape` is placed when requested to be at `pos`.
+    fn placement(&self, pos: Vec2, shape: &Shape) -> Vec2 {
+        let pos = match self.grid_snap {
+            Some(cell) => Self::snap_to_grid(pos, cell),
+            None => pos,
+        };
+        self.clamp_inside(pos, shape)
+    }
+
+    /// Clamp item position so that it stays inside the walls.
+    fn clamp_inside(&self, pos: Vec2, shape: &Shape) -> Vec2 {
+        let bound = (self.wall_size() - Vec2::splat(shape.radius())).max(Vec2::ZERO);
+        pos.clamp(-bound, bound)
+    }
+
+    /// Index of the item closest to `pos` among those within their radius plus grab tolerance.
+    pub fn item_at(&self, pos: Vec2) -> Option<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (i, pos.distance(*item.pos), item.shape.radius()))
+            .filter(|(_, dist, radius)| *dist < radius + self.grab_tolerance)
+            .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b))
+            .map(|(i, ..)| i)
+    }
+
+    /// Index of the item drawn on top among those under `pos`, see [`Self::item_at`] for hit criterion.
+    ///
+    /// Item with the greatest draw order is picked, the last inserted one if orders are equal.
+    pub fn topmost_item_at(&self, pos: Vec2) -> Option<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| pos.distance(*item.pos) < item.shape.radius() + self.grab_tolerance)
+            .max_by_key(|(i, item)| (item.order, *i))
+            .map(|(i, _)| i)
+    }
+
+    /// Indices of other items with centers within `range` from `i`-th item center, closest first.
+    ///
+    /// Returns nothing if there is no such item.
+    pub fn neighbors_of(&self, i: usize, range: f32) -> Vec<usize> {
+        let Some(item) = self.items.get(i) else {
+            return Vec::new();
+        };
+        let mut neighbors = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(j, other)| (j, item.pos.distance(*other.pos)))
+            .filter(|(_, dist)| *dist <= range)
+            .collect::<Vec<_>>();
+        neighbors.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        neighbors.into_iter().map(|(j, _)| j).collect()
+    }
+
+    pub fn grab_tolerance(&self) -> f32 {
+        self.grab_tolerance
+    }
+    /// Distance outside of items at which they can still be picked.
+    pub fn set_grab_tolerance(&mut self, tolerance: f32) {
+        self.grab_tolerance = tolerance.max(0.0);
+    }
+
     pub fn drag_acquire(&mut self, pos: Vec2) {
-        self.drag = self.items.iter().enumerate().find_map(|(i, item)| {
-            let rel_pos = pos - *item.pos;
-            if rel_pos.length() < item.shape.radius() {
-                let rpos = item.rot.inverse().transform(rel_pos);
-                Some((i, pos, rpos))
-            } else {
-                None
-            }
+        self.drag = self.topmost_item_at(pos).map(|i| {
+            let item = &self.items[i];
+            let rpos = item.rot.inverse().transform(pos - *item.pos);
+            (i, pos, rpos)
         })
     }
     pub fn drag_move(&mut self, pos: Vec2) {
-        if let Some((_, target, ..)) = &mut self.drag {
-            *target = pos;
+        if let Some((i, _, _)) = self.drag {
+            let pos = if self.clamp_drag_to_bounds {
+                self.clamp_inside(pos, &self.items[i].shape)
+            } else {
+                pos
+            };
+            if let Some((_, target, ..)) = &mut self.drag {
+                *target = pos;
+            }
         }
     }
+
+    pub fn clamp_drag_to_bounds(&self) -> bool {
+        self.clamp_drag_to_bounds
+    }
+    /// Keep drag target inside the walls so that dragged items are not flung through them.
+    pub fn set_clamp_drag_to_bounds(&mut self, clamp: bool) {
+        self.clamp_drag_to_bounds = clamp;
+    }
+
+    pub fn drag_stiffness(&self) -> f32 {
+        self.config.drag_stiffness
+    }
+    /// Set stiffness of the drag spring, larger values make the item follow the cursor faster.
+    ///
+    /// Same as [`PhysicsConfig::drag_stiffness`].
+    pub fn set_drag_stiffness(&mut self, stiffness: f32) {
+        self.config.drag_stiffness = stiffness.max(0.0);
+    }
+    pub fn drag_damping(&self) -> f32 {
+        self.config.mouse_damping
+    }
+    /// Set damping of dragged item velocity.
+    ///
+    /// Same as [`PhysicsConfig::mouse_damping`].
+    pub fn set_drag_damping(&mut self, damping: f32) {
+        self.config.mouse_damping = damping.max(0.0);
+    }
+    pub fn drag_style(&self) -> DragStyle {
+        self.drag_style
+    }
+    pub fn set_drag_style(&mut self, style: DragStyle) {
+        self.drag_style = style;
+    }
     pub fn drag_release(&mut self) {
+        if self.edit_mode
+            && let Some((i, ..)) = self.drag
+        {
+            // Put the item down where it was released
+            let item = &mut self.items[i];
+            item.vel = Var::default();
+            item.asp = Var::default();
+        }
         self.drag = None;
     }
 
+    pub fn followed_item(&self) -> Option<usize> {
+        self.followed
+    }
+    /// Select item for the camera to follow, cleared when the item is removed.
+    pub fn follow_item(&mut self, i: Option<usize>) {
+        self.followed = i.filter(|i| *i < self.items.len());
+    }
+    /// Position of the followed item.
+    pub fn follow_target(&self) -> Option<Vec2> {
+        Some(*self.items.get(self.followed?)?.pos)
+    }
+
+    /// Draw `i`-th item above all others.
+    pub fn bring_to_front(&mut self, i: usize) {
+        let top = self.items.iter().map(|item| item.order).max();
+        if let (Some(top), Some(item)) = (top, self.items.get_mut(i)) {
+            item.order = top.saturating_add(1);
+        }
+    }
+    /// Draw `i`-th item below all others, but still above the walls and background grid.
+    pub fn send_to_back(&mut self, i: usize) {
+        let bottom = self.items.iter().map(|item| item.order).min();
+        if let (Some(bottom), Some(item)) = (bottom, self.items.get_mut(i)) {
+            item.order = bottom.saturating_sub(1).max(draw::MIN_ITEM_ORDER);
+        }
+    }
+
+    pub fn edit_mode(&self) -> bool {
+        self.edit_mode
+    }
+    /// In edit mode simulation is frozen except for the dragged item.
+    pub fn set_edit_mode(&mut self, edit: bool) {
+        self.edit_mode = edit;
+    }
+
     pub fn n_items(&self) -> usize {
         self.items.len()
     }
-    pub fn remove_item(&mut self, i: usize) -> Item<S> {
-        self.drag = None;
-        self.items.remove(i)
+    pub fn item(&self, i: usize) -> Option<&Item<S>> {
+        self.items.get(i)
+    }
+    pub fn item_mut(&mut self, i: usize) -> Option<&mut Item<S>> {
+        self.items.get_mut(i)
+    }
+    pub fn items(&self) -> impl ExactSizeIterator<Item = &Item<S>> + '_ {
+        self.items.iter()
+    }
+    /// Indices of items having the `tag`.
+    pub fn items_with_tag(&self, tag: u64) -> impl Iterator<Item = usize> + '_ {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(move |(_, item)| item.tag == tag)
+            .map(|(i, _)| i)
+    }
+    /// Remove `i`-th item, returns `None` if there is no such item.
+    ///
+    /// Drag is released only if the removed item is being dragged.
+    pub fn remove_item(&mut self, i: usize) -> Option<Item<S>> {
+        if i >= self.items.len() {
+            return None;
+        }
+        let item = self.items.remove(i);
+        self.reindex_after_removal(i);
+        #[cfg(feature = "log")]
+        log::debug!("Item {i} removed, {} left", self.items.len());
+        Some(item)
     }
+
+    /// Update all stored item indices after `i`-th item was removed.
+    fn reindex_after_removal(&mut self, i: usize) {
+        self.drag = self
+            .drag
+            .and_then(|(j, target, loc_pos)| Some((reindex(j, i)?, target, loc_pos)));
+        self.followed = self.followed.and_then(|j| reindex(j, i));
+        self.reindex_joints(i);
+    }
+    /// Append item to the end of item list.
+    ///
+    /// If the world is full an item is evicted according to the [`EvictionPolicy`] first.
     pub fn insert_item(&mut self, item: Item<S>) {
+        self.make_room(1);
         self.items.push(item);
+        #[cfg(feature = "log")]
+        log::debug!("Item {} inserted", self.items.len() - 1);
     }
 
-    pub fn resize(&mut self, size: Vec2) {
-        self.size = size;
-    }
-    pub fn draw(&self, lib: &Library, scene: &mut Scene, mode: DrawMode) {
-        let wall_size = self.size - WALL_OFFSET * self.size.min_element();
-        match mode {
-            DrawMode::Normal => {
-                let thickness = 2.0 * WALL_OFFSET * self.size.max_element();
-                let wall_size = wall_size + 0.5 * thickness;
-                scene.add(
-                    &lib.shapes()
-                        .rectangle((
-                            -wall_size + Vec2::splat(0.5 * thickness),
-                            wall_size - Vec2::splat(0.5 * thickness),
-                        ))
-                        .fill_color(color::WHITE)
-                        .order(-1000),
-                );
-                /*
-                draw_rectangle_lines(
-                    -wall_size.x,
-                    -wall_size.y,
-                    2.0 * wall_size.x,
-                    2.0 * wall_size.y,
-                    thickness,
-                    color::WHITE,
-                );
-                */
+    /// Evict items according to the [`EvictionPolicy`] until `count` more items fit.
+    ///
+    /// Indices of remaining items may change, but items pushed afterwards are not affected.
+    pub(crate) fn make_room(&mut self, count: usize) {
+        if let Some(max_items) = self.max_items {
+            while !self.items.is_empty() && self.items.len() + count > max_items {
+                let i = match self.eviction {
+                    EvictionPolicy::Oldest => 0,
+                    EvictionPolicy::Random => self.rng.random_range(0..self.items.len()),
+                    EvictionPolicy::SlowestMoving => (0..self.items.len())
+                        .min_by(|&a, &b| {
+                            let (a, b) = (&self.items[a], &self.items[b]);
+                            a.kinetic_energy().total_cmp(&b.kinetic_energy())
+                        })
+                        .unwrap(),
+                };
+                #[cfg(feature = "log")]
+                log::debug!("Item {i} evicted by {:?} policy", self.eviction);
+                self.remove_item(i);
             }
-            DrawMode::Debug => {
-                /*
-                draw_rectangle_lines(
-                    -wall_size.x,
-                    -wall_size.y,
-                    2.0 * wall_size.x,
-                    2.0 * wall_size.y,
-                    0.3 * BORDERX,
-                    color::WHITE,
-                ),
-                */
+        }
+    }
+
+    /// Append many items at once, see [`Self::insert_item`].
+    pub fn insert_items(&mut self, items: impl IntoIterator<Item = Item<S>>) {
+        let items = items.into_iter();
+        let additional = items.size_hint().0;
+        self.items.reserve(match self.max_items {
+            Some(max_items) => additional.min(max_items.saturating_sub(self.items.len())),
+            None => additional,
+        });
+        for item in items {
+            self.insert_item(item);
+        }
+    }
+
+    pub fn max_items(&self) -> Option<usize> {
+        self.max_items
+    }
+    /// Limit number of items, zero limit is treated as one.
+    ///
+    /// Exceeding items are not removed until next insertion.
+    pub fn set_max_items(&mut self, max_items: Option<usize>) {
+        self.max_items = max_items.map(|n| n.max(1));
+    }
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.eviction
+    }
+    pub fn set_eviction_policy(&mut self, policy: EvictionPolicy) {
+        self.eviction = policy;
+    }
+    /// Instantly move `i`-th item to `pos` (snapped and clamped inside the walls) rotated by `angle`.
+    ///
+    /// Velocities are zeroed if `stop` is set. Drag of this item is released.
+    /// Returns `false` if there is no such item.
+    pub fn teleport_item(&mut self, i: usize, pos: Vec2, angle: f32, stop: bool) -> bool {
+        let Some(item) = self.items.get(i) else {
+            return false;
+        };
+        let pos = self.placement(pos, &item.shape);
+        let angle = if self.angle_snap {
+            (angle / ANGLE_SNAP).round() * ANGLE_SNAP
+        } else {
+            angle
+        };
+        let item = &mut self.items[i];
+        item.set_pose(pos, Rot2::from_angle(angle));
+        if stop {
+            item.vel = Var::default();
+            item.asp = Var::default();
+        }
+        if matches!(self.drag, Some((j, ..)) if j == i) {
+            self.drag = None;
+        }
+        true
+    }
+
+    /// Apply one-shot `impulse` to `i`-th item at world-space `point`.
+    ///
+    /// Returns `false` if there is no such item.
+    pub fn impulse_item(&mut self, i: usize, impulse: Vec2, point: Vec2) -> bool {
+        match self.items.get_mut(i) {
+            Some(item) => {
+                item.apply_impulse(impulse, point);
+                true
             }
+            None => false,
         }
-        for item in &self.items {
-            item.draw(lib, scene, mode);
+    }
+
+    /// Insert item placing it at `pos` (snapped and clamped inside the walls).
+    pub fn insert_item_at(&mut self, mut item: Item<S>, pos: Vec2) {
+        let rot = *item.rot;
+        item.set_pose(self.placement(pos, &item.shape), rot);
+        self.insert_item(item);
+    }
+
+    /// Remove all items and reset step counter and elapsed time.
+    ///
+    /// World settings and fluid regions are kept.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.drag = None;
+        self.followed = None;
+        self.joints.clear();
+        self.pour_remaining = 0;
+        self.max_penetration = 0.0;
+        self.total_overlap = 0.0;
+        self.step_count = 0;
+        self.elapsed = 0.0;
+    }
+
+    /// Change size keeping all items and settings, items left outside are pushed back by walls.
+    ///
+    /// Negative size is clamped to zero, simulation resumes once the world is not [degenerate](Self::is_degenerate).
+    pub fn resize(&mut self, size: Vec2) {
+        self.size = size.max(Vec2::ZERO);
+    }
+}
+
+/// Bright color with hue derived from `seed` regardless of any RNG state.
+pub fn color_from_seed(seed: u64) -> Rgb<f32> {
+    // SplitMix64 finalizer
+    let mut x = seed.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^= x >> 31;
+    Rgb::from(
+        HSL {
+            h: 360.0 * (x >> 11) as f64 / (1u64 << 53) as f64,
+            s: 1.0,
+            l: 0.5,
+        }
+        .to_rgb(),
+    ) / 255.0
+}
+
+/// Parameters of random item sampling.
+///
+/// Invalid values are fixed up on sampling: radius bounds are ordered and made non-negative,
+/// probabilities are clamped to `[0, 1]`, NaN is treated as zero.
+#[derive(Clone, Debug)]
+pub struct SampleParams {
+    /// Range of item radius.
+    pub radius: (f32, f32),
+    /// Probability of an item to be a circle rather than a rectangle.
+    pub circle_prob: f64,
+    /// Probability of an item to be a balloon which floats up.
+    pub balloon_prob: f64,
+    /// Probability of an item to be charged.
+    pub charge_prob: f64,
+    /// Magnitude of charge of charged items, sign is random.
+    pub charge: f32,
+}
+
+impl Default for SampleParams {
+    fn default() -> Self {
+        Self {
+            radius: (0.1, 0.3),
+            circle_prob: 0.5,
+            balloon_prob: 0.1,
+            charge_prob: 0.0,
+            charge: 1.0,
         }
     }
 }
 
-pub fn sample_item<S: Solver>(
+/// Uniform sample from `[low, high]`, or its value closest to zero if the range cannot be sampled, e.g. is infinite.
+fn sample_uniform(mut rng: impl Rng, low: f32, high: f32) -> f32 {
+    Uniform::new_inclusive(low, high).map_or(low.max(0.0).min(high), |dist| rng.sample(dist))
+}
+
+/// Sample random item inside the box, its color is determined by `color_seed`.
+pub fn sample_item<S: Solver>(rng: impl Rng, box_size: Vec2, color_seed: u64) -> Item<S> {
+    sample_item_with(rng, box_size, color_seed, &SampleParams::default())
+}
+
+/// Sample random item inside the box using `params`, its color is determined by `color_seed`.
+pub fn sample_item_with<S: Solver>(
     mut rng: impl Rng,
     box_size: Vec2,
-    textures: &TextureStorage,
+    color_seed: u64,
+    params: &SampleParams,
 ) -> Item<S> {
-    let radius: f32 = rng.sample(Uniform::new(0.1, 0.3).unwrap());
-    let mass = physics::MASF * radius;
+    // `max` returns the other argument if one is NaN
+    let probability = |p: f64| p.max(0.0).min(1.0);
+
+    let (a, b) = params.radius;
+    let min_radius = a.min(b).max(0.0);
+    let max_radius = a.max(b).max(min_radius);
+    let radius = sample_uniform(&mut rng, min_radius, max_radius);
     let eff_size = (box_size - Vec2::splat(radius)).max(Vec2::ZERO);
-    let shape = if rng.sample(Uniform::new(0.0, 1.0).unwrap()) < 0.5 {
+    let shape = if rng.random_bool(probability(params.circle_prob)) {
         Shape::Circle { radius }
     } else {
         Shape::Rectangle {
             size: Vec2::splat(radius),
         }
     };
-    Item {
-        body: Body {
-            mass,
-            pos: Var::new(Vec2::new(
-                rng.sample(Uniform::new_inclusive(-eff_size.x, eff_size.x).unwrap()),
-                rng.sample(Uniform::new_inclusive(-eff_size.y, eff_size.y).unwrap()),
-            )),
-            vel: Var::default(),
-            inm: physics::INMF * mass * radius,
-            rot: Var::new(Rot2::default()),
-            asp: Var::default(),
-        },
-        color: Rgb::from(
-            HSL {
-                h: rng.sample(Uniform::new(0.0, 360.0).unwrap()),
-                s: 1.0,
-                l: 0.5,
-            }
-            .to_rgb(),
-        ) / 255.0,
-        texture: match &shape {
-            Shape::Circle { .. } => textures.ball.clone(),
-            Shape::Rectangle { .. } => textures.noise.clone(),
-        },
-        shape,
+    let pos = Vec2::new(
+        sample_uniform(&mut rng, -eff_size.x, eff_size.x),
+        sample_uniform(&mut rng, -eff_size.y, eff_size.y),
+    );
+    let mut item = Item::new(shape, pos, color_from_seed(color_seed));
+    // Some items are balloons
+    if rng.random_bool(probability(params.balloon_prob)) {
+        item.gravity_scale = -0.5;
+    }
+    if rng.random_bool(probability(params.charge_prob)) {
+        item.charge = if rng.random_bool(0.5) {
+            params.charge
+        } else {
+            -params.charge
+        };
     }
+    item
 }
 
-const FORCEX: f32 = 0.05;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phy::Rk4;
 
-pub struct DrawActor<'a> {
-    pub lib: &'a Library,
-    pub scene: &'a mut Scene,
-}
+    const DT: f32 = 1.0 / 60.0;
 
-impl<S: Solver> Actor<S> for DrawActor<'_> {
-    fn apply(&mut self, _: &mut Body<S>, pos: Vec2, force: Vec2) {
-        let fpos = pos + FORCEX * force;
-        // Draw an arrow
-        self.scene.add(
-            &self
-                .lib
-                .shapes()
-                .triangle(
-                    fpos,
-                    pos - BORDERX * FORCEX * force.perp(),
-                    pos + BORDERX * FORCEX * force.perp(),
-                )
-                .fill_color(color::WHITE),
-        );
+    fn ball(radius: f32, pos: Vec2) -> Item<Rk4> {
+        Item::new(Shape::Circle { radius }, pos, Rgb::new(1.0, 1.0, 1.0))
     }
-}
 
-fn noisy_texture<R: Rng>(
-    rng: R,
-    lib: &Library,
-    width: u32,
-    height: u32,
-    base: Rgb<f32>,
-    var: Rgb<f32>,
-) -> Texture {
-    lib.make_texture(
-        &Image::with_data(
-            (width, height),
-            rng.sample_iter(Uniform::new(0.0, 1.0).unwrap())
-                .take(width as usize * height as usize)
-                .map(|a| {
-                    Vec4::from(((base.to_vec4() + a * var.to_vec4()).xyz(), 1.0)).to_rgba_f16()
-                })
-                .collect::<Vec<_>>(),
-        ),
-        TextureSettings::nearest(),
-    )
-}
+    fn assert_pose(pose: (Vec2, f32), pos: Vec2, angle: f32) {
+        assert!((pose.0 - pos).length() < 1e-5, "{:?} != {:?}", pose.0, pos);
+        assert!((pose.1 - angle).abs() < 1e-5, "{} != {}", pose.1, angle);
+    }
 
-pub struct TextureStorage {
-    ball: Texture,
-    noise: Texture,
-}
+    #[test]
+    fn interpolated_pose_bounds() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.insert_item(ball(0.1, Vec2::ZERO));
+        world.impulse_item(0, Vec2::new(0.1, 0.0), Vec2::new(0.0, 0.05));
+        let before = world.item(0).unwrap().clone();
+        world.advance(&mut Rk4, DT);
 
-impl TextureStorage {
-    pub async fn load(base: impl AsRef<Path>, rng: &mut impl Rng, lib: &Library) -> Self {
-        Self {
-            ball: lib
-                .load_texture(
-                    format!("{}/ball.png", base.as_ref()),
-                    TextureSettings::linear(),
-                )
-                .await
-                .unwrap(),
-            noise: noisy_texture(
-                rng,
-                lib,
-                32,
-                32,
-                Rgb::new(0.75, 0.75, 0.75),
-                Rgb::new(0.25, 0.25, 0.25),
-            ),
+        let item = world.item(0).unwrap();
+        assert!(item.pos.distance(*before.pos) > 0.0);
+        assert_pose(item.interpolated_pose(0.0), *before.pos, before.rot.angle());
+        assert_pose(item.interpolated_pose(1.0), *item.pos, item.rot.angle());
+    }
+
+    #[test]
+    fn teleport_resets_previous_pose() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.insert_item(ball(0.1, Vec2::ZERO));
+        world.advance(&mut Rk4, DT);
+        let (pos, angle) = (Vec2::new(0.3, -0.2), 0.5);
+        assert!(world.teleport_item(0, pos, angle, true));
+
+        let item = world.item(0).unwrap();
+        assert_pose(item.interpolated_pose(0.0), pos, angle);
+        assert_pose(item.interpolated_pose(1.0), pos, angle);
+    }
+
+    #[test]
+    fn teleport_stops_item_on_request() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let mut item = ball(0.1, Vec2::ZERO);
+        item.vel = Var::new(Vec2::new(0.5, 0.0));
+        item.asp = Var::new(1.0);
+        world.insert_item(item);
+        world.drag_acquire(Vec2::ZERO);
+        assert!(world.drag.is_some());
+
+        assert!(world.teleport_item(0, Vec2::new(0.3, 0.0), 0.0, false));
+        assert!(world.drag.is_none());
+        assert_eq!(*world.item(0).unwrap().vel, Vec2::new(0.5, 0.0));
+
+        // Position is clamped inside the walls
+        assert!(world.teleport_item(0, Vec2::new(5.0, 0.0), 0.0, true));
+        let item = world.item(0).unwrap();
+        assert_eq!(*item.pos, Vec2::new(world.wall_size().x - 0.1, 0.0));
+        assert_eq!(*item.vel, Vec2::ZERO);
+        assert_eq!(*item.asp, 0.0);
+        assert!(!world.teleport_item(1, Vec2::ZERO, 0.0, true));
+    }
+
+    #[test]
+    fn zero_size_world_pauses() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.insert_item(ball(0.1, Vec2::ZERO));
+        world.resize(Vec2::ZERO);
+        assert!(world.is_degenerate());
+        assert_eq!(world.spawn_random(), None);
+        assert_eq!(world.spawn_random_at(Vec2::ZERO), None);
+        for _ in 0..10 {
+            world.advance(&mut Rk4, DT);
+        }
+        assert_eq!(world.n_items(), 1);
+        assert_eq!(*world.item(0).unwrap().pos, Vec2::ZERO);
+
+        world.resize(Vec2::ONE);
+        assert!(!world.is_degenerate());
+        assert_eq!(world.spawn_random(), Some(1));
+        for _ in 0..10 {
+            world.advance(&mut Rk4, DT);
+        }
+        for item in world.items() {
+            assert!(item.pos.is_finite() && item.vel.is_finite());
+            assert!(item.pos.abs().cmple(world.wall_size()).all());
+        }
+    }
+
+    #[test]
+    fn insert_at_resets_previous_pose() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let pos = Vec2::new(-0.4, 0.1);
+        world.insert_item_at(ball(0.1, Vec2::ZERO), pos);
+
+        let item = world.item(0).unwrap();
+        assert_pose(item.interpolated_pose(0.0), pos, 0.0);
+        assert_pose(item.interpolated_pose(1.0), pos, 0.0);
+    }
+
+    #[test]
+    fn send_to_back_stays_above_background() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.insert_item(ball(0.1, Vec2::new(-0.5, 0.0)));
+        world.insert_item(ball(0.1, Vec2::new(0.5, 0.0)));
+        for _ in 0..2000 {
+            world.send_to_back(0);
+            world.send_to_back(1);
+        }
+        assert!(world.items().all(|item| item.order >= draw::MIN_ITEM_ORDER));
+    }
+
+    #[test]
+    fn sample_item_fixes_invalid_params() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let params = SampleParams {
+            radius: (0.3, 0.1),
+            circle_prob: 2.0,
+            balloon_prob: -1.0,
+            charge_prob: f64::NAN,
+            charge: 1.0,
+        };
+        for i in 0..100 {
+            let item = sample_item_with::<Rk4>(&mut rng, Vec2::ONE, i, &params);
+            assert!(
+                matches!(item.shape, Shape::Circle { radius } if (0.1..=0.3).contains(&radius))
+            );
+            assert_eq!(item.gravity_scale, 1.0);
+            assert_eq!(item.charge, 0.0);
+        }
+
+        let params = SampleParams {
+            radius: (-1.0, f32::NAN),
+            ..SampleParams::default()
+        };
+        let item = sample_item_with::<Rk4>(&mut rng, Vec2::INFINITY, 0, &params);
+        assert_eq!(item.shape.radius(), 0.0);
+        assert_eq!(*item.pos, Vec2::ZERO);
+    }
+
+    #[test]
+    fn wall_inset_is_symmetric() {
+        for size in [
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(8.0, 4.0),
+        ] {
+            let inset = size - World::<Rk4>::new(size).wall_size();
+            assert!((inset.x - WALL_OFFSET).abs() < 1e-6, "{inset:?}");
+            assert!((inset.y - WALL_OFFSET).abs() < 1e-6, "{inset:?}");
+        }
+    }
+
+    #[test]
+    fn drag_follows_item_after_removal() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for x in [-0.6, 0.0, 0.6] {
+            let mut item = ball(0.1, Vec2::new(x, 0.0));
+            item.gravity_scale = 0.0;
+            world.insert_item(item);
+        }
+        world.drag_acquire(Vec2::new(0.6, 0.0));
+        world.remove_item(0);
+        assert_eq!(world.drag.map(|(i, ..)| i), Some(1));
+
+        world.drag_move(Vec2::new(0.6, -0.5));
+        for _ in 0..30 {
+            world.advance(&mut Rk4, DT);
+        }
+        assert!(world.item(1).unwrap().pos.y < -0.1);
+        assert_eq!(*world.item(0).unwrap().pos, Vec2::ZERO);
+
+        world.remove_item(1);
+        assert!(world.drag.is_none());
+    }
+
+    #[test]
+    fn eviction_keeps_item_count() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_max_items(Some(3));
+        for x in [-0.4, -0.2, 0.0, 0.2, 0.4] {
+            world.insert_item(ball(0.05, Vec2::new(x, 0.0)));
+            assert!(world.n_items() <= 3);
+        }
+        let xs = world.items().map(|item| item.pos.x).collect::<Vec<_>>();
+        assert_eq!(xs, [0.0, 0.2, 0.4]);
+
+        world.set_eviction_policy(EvictionPolicy::SlowestMoving);
+        for (item, speed) in world.items.iter_mut().zip([1.0, 0.1, 2.0]) {
+            item.vel = Var::new(Vec2::new(0.0, speed));
+        }
+        world.insert_item(ball(0.05, Vec2::new(-0.4, 0.0)));
+        assert_eq!(world.n_items(), 3);
+        let xs = world.items().map(|item| item.pos.x).collect::<Vec<_>>();
+        assert_eq!(xs, [0.0, 0.4, -0.4]);
+    }
+
+    #[test]
+    fn topmost_item_respects_draw_order() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for (x, order) in [(0.0, 5), (0.05, 1)] {
+            let mut item = ball(0.1, Vec2::new(x, 0.0));
+            item.order = order;
+            world.insert_item(item);
+        }
+        let pos = Vec2::new(0.02, 0.0);
+        assert_eq!(world.topmost_item_at(pos), Some(0));
+        world.bring_to_front(1);
+        assert_eq!(world.topmost_item_at(pos), Some(1));
+        // The last inserted one wins on equal orders
+        world.item_mut(0).unwrap().order = world.item(1).unwrap().order;
+        assert_eq!(world.topmost_item_at(pos), Some(1));
+        assert_eq!(world.topmost_item_at(Vec2::new(0.5, 0.5)), None);
+
+        world.drag_acquire(pos);
+        assert_eq!(world.drag.map(|(i, ..)| i), Some(1));
+    }
+
+    #[test]
+    fn set_shape_updates_inertia() {
+        let mut item = ball(0.1, Vec2::ZERO);
+        let (mass, inm) = (item.mass, item.inm);
+
+        item.set_shape(Shape::Circle { radius: 0.2 });
+        assert!(matches!(item.shape(), Shape::Circle { radius } if *radius == 0.2));
+        assert_eq!(item.mass, mass);
+        assert!((item.inm - 2.0 * inm).abs() < 1e-6 * inm);
+
+        let square = Shape::Rectangle {
+            size: Vec2::splat(0.3),
+        };
+        let expected = square.moment_of_inertia(mass);
+        item.set_shape(square);
+        assert_eq!(item.inm, expected);
+
+        // The same tangential impulse spins it slower than the original ball
+        item.apply_impulse(Vec2::new(0.0, 0.01), Vec2::new(0.1, 0.0));
+        let mut original = ball(0.1, Vec2::ZERO);
+        original.apply_impulse(Vec2::new(0.0, 0.01), Vec2::new(0.1, 0.0));
+        assert!((*original.asp - 3.0 * *item.asp).abs() < 1e-4 * *original.asp);
+    }
+
+    #[test]
+    fn placement_snaps_to_grid() {
+        let snapped = World::<Rk4>::snap_to_grid(Vec2::new(0.12, 0.38), 0.1);
+        assert!(snapped.distance(Vec2::new(0.1, 0.4)) < 1e-6, "{snapped:?}");
+
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_grid_snap(Some(0.1));
+        world.set_angle_snap(true);
+        world.insert_item_at(ball(0.05, Vec2::ZERO), Vec2::new(0.12, 0.38));
+        assert!(world.item(0).unwrap().pos.distance(snapped) < 1e-6);
+
+        assert!(world.teleport_item(0, Vec2::new(-0.33, 0.07), 0.3, false));
+        let item = world.item(0).unwrap();
+        assert!(item.pos.distance(Vec2::new(-0.3, 0.1)) < 1e-6);
+        assert!((item.rot.angle() - ANGLE_SNAP).abs() < 1e-6);
+
+        // Non-positive cell disables snapping
+        world.set_grid_snap(Some(0.0));
+        assert_eq!(world.grid_snap(), None);
+    }
+
+    #[test]
+    fn grab_tolerance_picks_small_items() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.insert_item(ball(0.02, Vec2::new(-0.1, 0.0)));
+        world.insert_item(ball(0.02, Vec2::new(0.1, 0.0)));
+        let click = Vec2::new(0.13, 0.0);
+
+        world.set_grab_tolerance(0.0);
+        world.drag_acquire(click);
+        assert!(world.drag.is_none());
+
+        world.set_grab_tolerance(0.02);
+        world.drag_acquire(click);
+        assert!(matches!(world.drag, Some((1, ..))));
+
+        // Closest center wins when several items are within tolerance
+        world.set_grab_tolerance(0.5);
+        assert_eq!(world.item_at(Vec2::new(0.05, 0.0)), Some(1));
+        assert_eq!(world.item_at(Vec2::new(-0.05, 0.0)), Some(0));
+    }
+
+    #[test]
+    fn clamped_drag_keeps_item_inside() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_clamp_drag_to_bounds(true);
+        world.insert_item(ball(0.05, Vec2::new(0.5, 0.0)));
+        world.drag_acquire(Vec2::new(0.5, 0.0));
+        world.drag_move(Vec2::new(10.0, 0.0));
+        let bound = world.wall_size().x - 0.05;
+        assert!(matches!(world.drag, Some((0, target, _)) if target.x <= bound));
+
+        for _ in 0..60 {
+            world.advance(&mut Rk4, DT);
+            assert!(world.item(0).unwrap().pos.x < world.wall_size().x);
         }
     }
+
+    #[test]
+    fn neighbors_are_sorted_by_distance() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for pos in [
+            Vec2::ZERO,
+            Vec2::new(0.3, 0.0),
+            Vec2::new(0.0, -0.1),
+            Vec2::new(-0.2, 0.0),
+            Vec2::new(0.5, 0.5),
+        ] {
+            world.insert_item(ball(0.02, pos));
+        }
+        assert_eq!(world.neighbors_of(0, 0.35), [2, 3, 1]);
+        assert_eq!(world.neighbors_of(0, 0.15), [2]);
+        assert!(world.neighbors_of(4, 0.1).is_empty());
+        assert!(world.neighbors_of(5, 1.0).is_empty());
+    }
+
+    #[test]
+    fn following_clears_on_removal() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for x in [-0.5, 0.0, 0.5] {
+            world.insert_item(ball(0.05, Vec2::new(x, 0.0)));
+        }
+        world.follow_item(Some(3));
+        assert_eq!(world.followed_item(), None);
+
+        world.follow_item(Some(1));
+        assert_eq!(world.follow_target(), Some(Vec2::ZERO));
+        // Following moves with the item when indices shift
+        world.remove_item(0);
+        assert_eq!(world.followed_item(), Some(0));
+        assert_eq!(world.follow_target(), Some(Vec2::ZERO));
+        world.remove_item(0);
+        assert_eq!(world.followed_item(), None);
+        assert_eq!(world.follow_target(), None);
+    }
+
+    #[test]
+    fn bulk_insertion_keeps_order_and_limit() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let balls = || (0..100).map(|k| ball(0.01, Vec2::new(0.01 * k as f32 - 0.5, 0.0)));
+        world.insert_items(balls());
+        assert_eq!(world.n_items(), 100);
+        assert_eq!(
+            *world.item(99).unwrap().pos,
+            *ball(0.01, Vec2::new(0.49, 0.0)).pos
+        );
+
+        world.set_max_items(Some(150));
+        world.insert_items(balls());
+        assert_eq!(world.n_items(), 150);
+    }
+
+    #[test]
+    fn stiffer_drag_reaches_target_faster() {
+        let steps_to_reach = |stiffness: f32| {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            world.set_drag_stiffness(stiffness);
+            let mut item = ball(0.1, Vec2::new(-0.5, 0.0));
+            item.gravity_scale = 0.0;
+            world.insert_item(item);
+            world.drag_acquire(Vec2::new(-0.5, 0.0));
+            let target = Vec2::new(0.5, 0.0);
+            world.drag_move(target);
+            (1..=600)
+                .find(|_| {
+                    world.advance(&mut Rk4, DT);
+                    world.item(0).unwrap().pos.distance(target) < 0.05
+                })
+                .unwrap_or(usize::MAX)
+        };
+        let stiffness = World::<Rk4>::new(Vec2::ONE).drag_stiffness();
+        let (soft, stiff) = (steps_to_reach(stiffness), steps_to_reach(4.0 * stiffness));
+        assert!(soft < usize::MAX);
+        assert!(stiff < soft, "{stiff} >= {soft}");
+    }
 }
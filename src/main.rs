@@ -1,17 +1,18 @@
 use std::time::Duration;
 
-use bounce::{DrawActor, DrawMode, TextureStorage, World, sample_item};
-use glam::{Vec4, Vec4Swizzles};
-use phy::{Rk4, Solver};
+use bounce::{
+    DrawActor, DrawMode, MissingTexturePolicy, RENDER_SCALE, TextureStorage, World, WorldView,
+};
+use phy::Rk4;
 use rand::{Rng, SeedableRng, rngs::SmallRng};
 use rand_distr::Uniform;
 use wgame::{
     Event, Library, Window,
     app::time::Instant,
     gfx::types::{Color, color},
-    glam::{Affine2, Vec2},
+    glam::Vec2,
     input::{
-        event::{ElementState, MouseButton},
+        event::{ElementState, MouseButton, MouseScrollDelta},
         keyboard::{KeyCode, PhysicalKey},
     },
     prelude::*,
@@ -23,53 +24,52 @@ async fn main(mut window: Window<'_>) {
     let gfx = Library::new(window.graphics());
 
     let mut rng = SmallRng::seed_from_u64(0xdeadbeef);
-    let textures = TextureStorage::load("assets", &mut rng, &gfx).await;
+    let textures = match TextureStorage::load(
+        "assets",
+        &mut rng,
+        &gfx,
+        MissingTexturePolicy::Fallback,
+    )
+    .await
+    {
+        Ok(textures) => textures,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return;
+        }
+    };
 
     // let font = gfx.load_font("assets/free-sans-bold.ttf").await.unwrap();
     // let mut font_raster = None;
     // let mut text = None;
 
     let mut viewport = Vec2::ZERO;
-    let scale = 640.0;
+    let mut view = WorldView::new(RENDER_SCALE);
 
-    let mut toy_box: Option<World<Rk4>> = None;
+    // Actual size is set on the first resize
+    let mut toy_box = World::<Rk4>::default();
+    for _ in 0..8 {
+        toy_box.spawn_random();
+    }
     let mut mode = DrawMode::Normal;
 
     let mut events = window.input();
     let mut mouse_pos = Vec2::ZERO;
     let mut mouse_down = false;
+    // Last cursor position in screen pixels, used for panning
+    let mut cursor = Vec2::ZERO;
+    let mut panning = false;
 
     let mut time = Instant::now();
     'frame_loop: while let Some(mut frame) = window.next_frame().await.unwrap() {
         if let Some((width, height)) = frame.resized() {
             viewport = Vec2::new(width as f32, height as f32);
-            toy_box = Some(match toy_box.take() {
-                None => {
-                    let mut toy_box = World::new(viewport / scale);
-                    for _ in 0..8 {
-                        toy_box.insert_item(sample_item(&mut rng, toy_box.size(), &textures));
-                    }
-                    toy_box
-                }
-                Some(mut toy_box) => {
-                    toy_box.resize(viewport / scale);
-                    toy_box
-                }
-            });
+            toy_box.resize(view.world_size(viewport));
 
             // let raster = font_raster.insert(font.rasterize(height as f32 / 10.0));
             // text = Some(raster.text("Hello, World!"));
         }
 
-        let toy_box = toy_box.as_mut().unwrap();
-        let camera = frame
-            .physical_camera()
-            .transform(Affine2::from_scale_angle_translation(
-                Vec2::splat(0.5 * scale),
-                0.0,
-                0.5 * viewport,
-            ));
-
         while let Some(event) = events.try_next() {
             match event {
                 Event::KeyboardInput { event, .. } => {
@@ -80,11 +80,7 @@ async fn main(mut window: Window<'_>) {
                         match key {
                             KeyCode::Escape => break 'frame_loop,
                             KeyCode::Equal | KeyCode::NumpadAdd => {
-                                toy_box.insert_item(sample_item(
-                                    &mut rng,
-                                    toy_box.size(),
-                                    &textures,
-                                ));
+                                toy_box.spawn_random();
                             }
                             KeyCode::Minus | KeyCode::NumpadSubtract => {
                                 if toy_box.n_items() != 0 {
@@ -93,11 +89,27 @@ async fn main(mut window: Window<'_>) {
                                     );
                                 }
                             }
+                            KeyCode::KeyF => {
+                                // Follow item under cursor or stop following
+                                toy_box.follow_item(match toy_box.followed_item() {
+                                    Some(_) => None,
+                                    None => toy_box.item_at(mouse_pos),
+                                });
+                            }
+                            KeyCode::Space => {
+                                toy_box.set_edit_mode(!toy_box.edit_mode());
+                            }
                             KeyCode::Backslash => {
                                 mode = match mode {
                                     DrawMode::Normal => DrawMode::Debug,
-                                    DrawMode::Debug => DrawMode::Normal,
-                                }
+                                    DrawMode::Debug => DrawMode::LayerView,
+                                    DrawMode::LayerView => DrawMode::Normal,
+                                };
+                                // Slow motion in debug mode
+                                toy_box.set_time_scale(match mode {
+                                    DrawMode::Debug => 0.1,
+                                    _ => 1.0,
+                                });
                             }
                             _ => (),
                         }
@@ -112,48 +124,67 @@ async fn main(mut window: Window<'_>) {
                         mouse_down = false;
                         toy_box.drag_release();
                     }
+                    (ElementState::Pressed, MouseButton::Right) => {
+                        toy_box.spawn_random_at(mouse_pos);
+                    }
+                    (ElementState::Pressed, MouseButton::Middle) => {
+                        panning = true;
+                    }
+                    (ElementState::Released, MouseButton::Middle) => {
+                        panning = false;
+                    }
                     _ => (),
                 },
                 Event::CursorMoved { position, .. } => {
-                    let world_pos = camera.logical_to_world(Vec4::new(
-                        2.0 * position.x as f32 / viewport.x - 1.0,
-                        1.0 - 2.0 * position.y as f32 / viewport.y,
-                        0.0,
-                        1.0,
-                    ));
-                    mouse_pos = world_pos.xy();
+                    let position = Vec2::new(position.x as f32, position.y as f32);
+                    if panning {
+                        view.pan(position - cursor, viewport);
+                    }
+                    cursor = position;
+                    mouse_pos = view.screen_to_world(cursor, viewport);
 
                     if mouse_down {
                         toy_box.drag_move(mouse_pos);
                     }
                 }
+                Event::MouseWheel { delta, .. } => {
+                    let lines = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => y,
+                        MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 40.0,
+                    };
+                    view.zoom(0.1 * lines, cursor, viewport);
+                    mouse_pos = view.screen_to_world(cursor, viewport);
+                }
                 Event::CursorLeft { .. } => {
                     mouse_down = false;
+                    panning = false;
                 }
                 _ => (),
             }
         }
 
-        frame.clear(match mode {
-            DrawMode::Normal => color::BLACK.mix(color::WHITE, 0.5),
-            DrawMode::Debug => color::BLACK.to_rgba(),
-        });
-
-        let mut scene = frame.scene();
-        scene.camera = camera;
+        frame.clear(toy_box.background(mode).to_rgba());
 
         {
             let now = Instant::now();
             let frame_time = now - time;
             time = now;
-            let dt = frame_time
-                .min(Duration::from_millis(40))
-                .div_f32(if mode == DrawMode::Debug { 10.0 } else { 1.0 });
-            Rk4.solve_step(toy_box, dt.as_secs_f32());
+            let dt = frame_time.min(Duration::from_millis(40));
+            toy_box.advance(&mut Rk4, dt.as_secs_f32());
+            // Manual pan overrides following
+            if !panning && let Some(target) = toy_box.follow_target() {
+                view.follow(target, dt.as_secs_f32(), viewport);
+            }
         }
 
+        let camera = frame.physical_camera().transform(view.transform(viewport));
+        let mut scene = frame.scene();
+        scene.camera = camera;
+
         {
-            toy_box.draw(&gfx, &mut scene, mode);
+            // Zoom changes the pixel size of world units
+            toy_box.set_render_scale(view.render_scale());
+            toy_box.draw(&gfx, &mut scene, &textures, mode);
             if mode == DrawMode::Debug {
                 toy_box.compute_derivs_ext(&mut DrawActor {
                     lib: &gfx,
@@ -1,4 +1,4 @@
-use super::{Item, World};
+use super::{Item, TextureId, World};
 use either::Either;
 use geom2::{
     ArcVertex, Circle, Disk, HalfPlane, Integrable, Intersect, IntersectTo, LineSegment, Meta,
@@ -6,10 +6,15 @@ use geom2::{
 };
 use glam::Vec2;
 use phy::{Rot2, Solver, System, Var, Visitor, angular_to_linear2, torque2};
+use rgb::Rgb;
 use smallvec::SmallVec;
+use std::f32::consts::{PI, TAU};
 
 const AREA_EPS: f32 = 0.0;
 
+/// Number of segments approximating a rounded rectangle corner.
+const CORNER_SEGMENTS: usize = 4;
+
 /// Mass factor
 pub const MASF: f32 = 1.0;
 /// Moment of inertia factor
@@ -19,6 +24,8 @@ pub const INMF: f32 = 0.2;
 const GRAV: Vec2 = Vec2::new(0.0, 4.0);
 /// Air resistance
 const AIRF: f32 = 0.01;
+/// Quadratic air resistance
+const AIRF2: f32 = 0.02;
 
 /// Elasticity of balls
 const ELAST: f32 = 200.0;
@@ -27,12 +34,33 @@ const ELAST: f32 = 200.0;
 const DAMP: f32 = 0.2;
 /// Liquid friction
 const FRICT: f32 = 0.4;
+/// Friction factor of sticking contact in Coulomb model.
+const STICK_FRICT: f32 = 40.0;
 
 /// Mouse attraction damping.
 const MOUSE_DAMP: f32 = 4.0;
 
-/// Wall offset factor
-pub const WALL_OFFSET: f32 = 0.04;
+/// Drag inside fluid per unit of submerged area and fluid density
+const FLUID_DRAG: f32 = 2.0;
+
+/// Penetration depth left uncorrected by position correction.
+const CORRECTION_SLOP: f32 = 0.005;
+/// Position correction factor suitable for multiple [solver iterations](World::set_solver_iterations).
+pub const ITERATION_CORRECTION: f32 = 0.2;
+
+/// Fraction of total item area overlapping when the world is considered jammed
+pub const JAM_OVERLAP: f32 = 0.05;
+
+/// Electrostatic force factor
+const COULOMB: f32 = 1.0;
+/// Distance softening electrostatic force at close range
+const CHARGE_SOFTENING: f32 = 0.1;
+
+/// Acceleration per unit of depth in the wall cushion.
+const CUSHION_STIFF: f32 = 400.0;
+
+/// Gap between world bounds and walls in world units, the same on all sides regardless of world size.
+pub const WALL_OFFSET: f32 = 0.05;
 
 #[derive(Clone, Debug)]
 pub enum Shape {
@@ -43,53 +71,475 @@ pub enum Shape {
         /// Half len of rectangle sides
         size: Vec2,
     },
+    /// Rectangle with rounded corners, avoids spiky forces from corner contacts.
+    RoundedRectangle {
+        /// Half len of rectangle sides
+        size: Vec2,
+        /// Radius of corners, clamped to the half of the shorter side.
+        corner_radius: f32,
+    },
+    /// Hollow circle, other items can be inside of the hole.
+    ///
+    /// Only circles collide with the inner boundary, other shapes see it as a disk.
+    Ring {
+        outer: f32,
+        inner: f32,
+    },
+    /// Several simple shapes moving as a single rigid body.
+    Compound {
+        parts: Vec<CompoundPart>,
+    },
 }
 
 impl Shape {
+    pub fn area(&self) -> f32 {
+        match self {
+            Shape::Circle { radius } => PI * radius * radius,
+            Shape::Rectangle { size } => 4.0 * size.x * size.y,
+            Shape::RoundedRectangle {
+                size,
+                corner_radius,
+            } => {
+                let r = corner_radius.clamp(0.0, size.min_element());
+                4.0 * size.x * size.y - (4.0 - PI) * r * r
+            }
+            Shape::Ring { outer, inner } => PI * (outer * outer - inner * inner),
+            Shape::Compound { parts } => parts.iter().map(|part| part.shape.area()).sum(),
+        }
+    }
+
     pub fn radius(&self) -> f32 {
         match self {
             Shape::Circle { radius } => *radius,
-            Shape::Rectangle { size } => size.min_element(),
+            Shape::Rectangle { size } | Shape::RoundedRectangle { size, .. } => size.min_element(),
+            Shape::Ring { outer, .. } => *outer,
+            Shape::Compound { parts } => parts
+                .iter()
+                .map(|part| part.offset.length() + part.shape.radius())
+                .fold(0.0, f32::max),
         }
     }
+
+    /// Moment of inertia of a body of this shape with given `mass`, derived from shape radius.
+    pub fn moment_of_inertia(&self, mass: f32) -> f32 {
+        INMF * mass * self.radius()
+    }
 }
 
-impl<S: Solver> Item<S> {
-    pub fn geometry(&self) -> Either<Disk, Polygon<SmallVec<[Vec2; 4]>>> {
+/// Outline of a rounded rectangle in body space, corners are approximated by [`CORNER_SEGMENTS`] segments.
+///
+/// Coinciding points are merged, so zero radius gives a plain rectangle.
+pub(crate) fn rounded_rectangle_points(size: Vec2, corner_radius: f32) -> SmallVec<[Vec2; 4]> {
+    let r = corner_radius.clamp(0.0, size.min_element());
+    let inner = size - r;
+    let mut points = [
+        Vec2::new(-inner.x, -inner.y),
+        Vec2::new(inner.x, -inner.y),
+        Vec2::new(inner.x, inner.y),
+        Vec2::new(-inner.x, inner.y),
+    ]
+    .into_iter()
+    .enumerate()
+    .flat_map(|(k, corner)| {
+        (0..=CORNER_SEGMENTS).map(move |i| {
+            let angle = PI + 0.5 * PI * (k as f32 + i as f32 / CORNER_SEGMENTS as f32);
+            corner + r * Vec2::from_angle(angle)
+        })
+    })
+    .collect::<SmallVec<[Vec2; 4]>>();
+    // Arcs degenerate to points for zero radius and adjacent arcs meet if the radius is the half of a side
+    let eps = 1e-6 * size.max_element();
+    points.dedup_by(|a, b| a.distance(*b) <= eps);
+    if points.len() > 1 && points[0].distance(points[points.len() - 1]) <= eps {
+        points.pop();
+    }
+    points
+}
+
+/// Simple shape of a compound body with properties of the item it was made from.
+#[derive(Clone, Debug)]
+pub struct CompoundPart {
+    /// Never compound.
+    pub shape: Shape,
+    /// Position relative to the body center in body space.
+    pub offset: Vec2,
+    /// Rotation relative to the body.
+    pub angle: f32,
+
+    pub mass: f32,
+    /// Moment of inertia about the part center.
+    pub inm: f32,
+    pub texture: Option<TextureId>,
+    pub color: Rgb<f32>,
+}
+
+/// Shape placed in the world, detached from body dynamics.
+#[derive(Clone, Debug)]
+pub struct Collider {
+    pub shape: Shape,
+    pub pos: Vec2,
+    pub angle: f32,
+}
+
+impl Collider {
+    /// Simple colliders the collider consists of.
+    ///
+    /// Simple collider consists only of itself.
+    pub fn parts(&self) -> SmallVec<[Collider; 1]> {
+        match &self.shape {
+            Shape::Compound { parts } => {
+                let rot = Vec2::from_angle(self.angle);
+                parts
+                    .iter()
+                    .map(|part| Collider {
+                        shape: part.shape.clone(),
+                        pos: self.pos + rot.rotate(part.offset),
+                        angle: self.angle + part.angle,
+                    })
+                    .collect()
+            }
+            _ => SmallVec::from_buf([self.clone()]),
+        }
+    }
+
+    /// Geometries of all [parts](Self::parts), ring is represented by its outer disk.
+    pub fn geometries(&self) -> SmallVec<[Either<Disk, Polygon<SmallVec<[Vec2; 4]>>>; 1]> {
+        self.parts().iter().map(Collider::geometry).collect()
+    }
+
+    /// Geometry of a simple collider, compound ones must be split into [parts](Self::parts) first.
+    fn geometry(&self) -> Either<Disk, Polygon<SmallVec<[Vec2; 4]>>> {
         match self.shape {
-            Shape::Circle { radius } => Either::Left(Disk(Circle {
-                center: *self.pos,
-                radius,
-            })),
-            Shape::Rectangle { size } => {
-                let vertex = Vec2::from_angle(self.rot.angle()).rotate(size);
-                Either::Right(Polygon::<SmallVec<[Vec2; 4]>>::new(SmallVec::from([
-                    *self.pos - vertex,
-                    *self.pos - vertex.perp(),
-                    *self.pos + vertex,
-                    *self.pos + vertex.perp(),
-                ])))
+            Shape::Circle { radius } | Shape::Ring { outer: radius, .. } => {
+                Either::Left(Disk(Circle {
+                    center: self.pos,
+                    radius,
+                }))
+            }
+            Shape::Rectangle { .. } | Shape::RoundedRectangle { .. } => {
+                Either::Right(Polygon::new(self.vertices().unwrap()))
+            }
+            Shape::Compound { .. } => panic!("Compound shape has no single geometry"),
+        }
+    }
+
+    /// Vertices of a polygonal collider in world space, `None` for round and compound shapes.
+    fn vertices(&self) -> Option<SmallVec<[Vec2; 4]>> {
+        let mut vertices = match self.shape {
+            Shape::Rectangle { size } => SmallVec::from([
+                Vec2::new(-size.x, -size.y),
+                Vec2::new(size.x, -size.y),
+                Vec2::new(size.x, size.y),
+                Vec2::new(-size.x, size.y),
+            ]),
+            Shape::RoundedRectangle {
+                size,
+                corner_radius,
+            } => rounded_rectangle_points(size, corner_radius),
+            _ => return None,
+        };
+        let rot = Vec2::from_angle(self.angle);
+        for v in vertices.iter_mut() {
+            *v = self.pos + rot.rotate(*v);
+        }
+        Some(vertices)
+    }
+}
+
+impl Collider {
+    /// Axis-aligned bounding box as `(min, max)` corners.
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        let half = match &self.shape {
+            Shape::Circle { radius } | Shape::Ring { outer: radius, .. } => Vec2::splat(*radius),
+            Shape::Rectangle { size } | Shape::RoundedRectangle { size, .. } => {
+                let (sin, cos) = self.angle.sin_cos();
+                Vec2::new(
+                    cos.abs() * size.x + sin.abs() * size.y,
+                    sin.abs() * size.x + cos.abs() * size.y,
+                )
             }
+            Shape::Compound { .. } => {
+                return self
+                    .parts()
+                    .iter()
+                    .map(Collider::aabb)
+                    .reduce(|(a_min, a_max), (b_min, b_max)| (a_min.min(b_min), a_max.max(b_max)))
+                    .unwrap_or((self.pos, self.pos));
+            }
+        };
+        (self.pos - half, self.pos + half)
+    }
+}
+
+impl<S: Solver> Item<S> {
+    pub fn collider(&self) -> Collider {
+        Collider {
+            shape: self.shape.clone(),
+            pos: *self.pos,
+            angle: self.rot.angle(),
         }
     }
+
+    /// World-space geometries of the item, one per part of a compound shape.
+    pub fn geometry(&self) -> SmallVec<[Either<Disk, Polygon<SmallVec<[Vec2; 4]>>>; 1]> {
+        self.collider().geometries()
+    }
+    /// World-space axis-aligned bounding box as `(min, max)` corners.
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        self.collider().aabb()
+    }
+
+    /// Whether linear and angular speeds are below `threshold` while touching something.
+    ///
+    /// Contacts are taken from the end of the last step.
+    pub fn is_resting(&self, threshold: f32) -> bool {
+        self.contacts > 0 && self.vel.length() < threshold && self.asp.abs() < threshold
+    }
+
+    /// Pose `(position, angle)` between the beginning (`alpha = 0`) and the end (`alpha = 1`) of the last step.
+    ///
+    /// Rotation is interpolated along the shortest arc.
+    pub fn interpolated_pose(&self, alpha: f32) -> (Vec2, f32) {
+        let prev_angle = self.prev_rot.angle();
+        let delta = (self.rot.angle() - prev_angle + PI).rem_euclid(TAU) - PI;
+        (
+            self.prev_pos.lerp(*self.pos, alpha),
+            prev_angle + alpha * delta,
+        )
+    }
 }
 
+/// Receiver of all forces computed during the derivative pass.
 pub trait Actor<S: Solver> {
     /// Apply force to the specific point of the body.
+    ///
+    /// Both `pos` and `force` are given in world space.
     fn apply(&mut self, body: &mut Body<S>, pos: Vec2, force: Vec2);
+
+    /// Forces applied next act on `item` and are caused by `other` item.
+    ///
+    /// `None` means walls, fields and other external forces.
+    fn interaction(&mut self, item: usize, other: Option<usize>) {
+        let _ = (item, other);
+    }
+}
+
+impl<S: Solver, A: Actor<S> + ?Sized> Actor<S> for &mut A {
+    fn apply(&mut self, body: &mut Body<S>, pos: Vec2, force: Vec2) {
+        (**self).apply(body, pos, force);
+    }
+    fn interaction(&mut self, item: usize, other: Option<usize>) {
+        (**self).interaction(item, other);
+    }
+}
+
+/// Forwards each force to both actors in order.
+impl<S: Solver, A: Actor<S>, B: Actor<S>> Actor<S> for (A, B) {
+    fn apply(&mut self, body: &mut Body<S>, pos: Vec2, force: Vec2) {
+        self.0.apply(body, pos, force);
+        self.1.apply(body, pos, force);
+    }
+    fn interaction(&mut self, item: usize, other: Option<usize>) {
+        self.0.interaction(item, other);
+        self.1.interaction(item, other);
+    }
 }
 
-struct DerivActor;
+/// Actor that accumulates forces into body derivatives.
+///
+/// This is the actor used for integration, it can be wrapped to additionally observe forces.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DerivActor;
 impl<S: Solver> Actor<S> for DerivActor {
     fn apply(&mut self, body: &mut Body<S>, pos: Vec2, force: Vec2) {
-        body.vel.deriv += force / body.mass;
-        body.asp.deriv += torque2(pos - *body.pos, force) / body.inm;
+        body.vel.deriv += force * body.inv_mass();
+        body.asp.deriv += torque2(pos - *body.pos, force) * body.inv_inm();
+    }
+}
+
+/// Rectangular region filled with fluid.
+#[derive(Clone, Debug)]
+pub struct FluidRegion {
+    pub min: Vec2,
+    pub max: Vec2,
+    /// Mass per unit area.
+    pub density: f32,
+}
+
+impl FluidRegion {
+    fn collider(&self) -> Collider {
+        Collider {
+            shape: Shape::Rectangle {
+                size: 0.5 * (self.max - self.min),
+            },
+            pos: 0.5 * (self.min + self.max),
+            angle: 0.0,
+        }
+    }
+}
+
+/// How a body responds to forces.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum BodyKind {
+    /// Moved by forces.
+    #[default]
+    Dynamic,
+    /// Never moves.
+    Static,
+    /// Moves with prescribed velocity ignoring all forces, pushes dynamic bodies.
+    Kinematic,
+}
+
+/// Air resistance model.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DragModel {
+    /// Drag force proportional to velocity.
+    #[default]
+    Linear,
+    /// Drag force proportional to squared velocity, stops fast bodies more effectively.
+    Quadratic,
+}
+
+/// How a dragged item is pulled towards the cursor.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DragStyle {
+    /// Damped spring, may overshoot at large time steps.
+    #[default]
+    Spring,
+    /// Velocity of the grabbed point is set to approach the target exponentially
+    /// with [`DRAG_TIME`] time constant, stable at any time step.
+    CriticallyDamped,
+}
+
+/// Time constant of [`DragStyle::CriticallyDamped`] drag.
+pub const DRAG_TIME: f32 = 0.05;
+
+/// Global contact and drag coefficients tunable at runtime.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PhysicsConfig {
+    /// Contact stiffness, i.e. force per unit of overlap.
+    ///
+    /// It is not a restitution: zero stiffness disables contact forces at all, see [`Self::restitution`].
+    pub elasticity: f32,
+    /// Fraction of normal speed kept after a bounce, `0.0` makes contacts perfectly inelastic.
+    ///
+    /// Elastic force is weakened by its square while bodies move apart, so that the energy
+    /// returned is reduced accordingly. Energy is also lost through [`Self::damping`].
+    pub restitution: f32,
+    /// Contact damping relative to elastic force.
+    pub damping: f32,
+    /// Liquid friction factor.
+    pub friction: f32,
+    /// Stiffness of the spring pulling dragged item, independent of contact [elasticity](Self::elasticity).
+    pub drag_stiffness: f32,
+    /// Drag attraction damping.
+    pub mouse_damping: f32,
+    /// Limit of total force magnitude at a contact, shared by all its points.
+    pub max_contact_force: Option<f32>,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            elasticity: ELAST,
+            restitution: 1.0,
+            damping: DAMP,
+            friction: FRICT,
+            drag_stiffness: ELAST,
+            mouse_damping: MOUSE_DAMP,
+            max_contact_force: None,
+        }
+    }
+}
+
+/// How the magnitude of contact force is derived from overlap.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum ContactModel {
+    /// Force proportional to overlap area.
+    #[default]
+    Area,
+    /// Force proportional to square root of overlap area, softer for large overlaps.
+    AreaSqrt,
+    /// Force proportional to penetration depth estimated from area and contact width.
+    PenetrationDepth,
+}
+
+impl ContactModel {
+    /// Contact force magnitude for overlap `area` of bodies with minimal `radius`.
+    pub fn force(&self, area: f32, radius: f32) -> f32 {
+        match self {
+            ContactModel::Area => area,
+            ContactModel::AreaSqrt => area.sqrt(),
+            ContactModel::PenetrationDepth => penetration(area, radius),
+        }
+    }
+}
+
+/// Tangential friction model.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum FrictionModel {
+    /// Friction force proportional to tangential velocity.
+    #[default]
+    Liquid,
+    /// Dry friction bounded by normal force, makes round bodies roll.
+    Coulomb,
+}
+
+/// Surface properties of a body.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Material {
+    pub friction_model: FrictionModel,
+    /// Static friction coefficient.
+    pub static_friction: f32,
+    /// Kinetic friction coefficient.
+    pub kinetic_friction: f32,
+    /// Bodies touching this surface are welded into a single compound body.
+    pub weld_on_contact: bool,
+    /// Restitution multiplier, `1.0` keeps the global [restitution](PhysicsConfig::restitution)
+    /// and `0.0` makes the surface dead. Values above one make it bouncier.
+    ///
+    /// Unlike [`PhysicsConfig::elasticity`] it doesn't change contact stiffness.
+    pub elasticity: f32,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            friction_model: FrictionModel::Liquid,
+            static_friction: 0.6,
+            kinetic_friction: 0.4,
+            weld_on_contact: false,
+            elasticity: 1.0,
+        }
+    }
+}
+
+impl Material {
+    /// Material of contact between two surfaces.
+    ///
+    /// Coulomb friction is used and welding occurs if any of surfaces requires it.
+    pub fn combine(&self, other: &Self) -> Self {
+        Self {
+            friction_model: match (self.friction_model, other.friction_model) {
+                (FrictionModel::Liquid, FrictionModel::Liquid) => FrictionModel::Liquid,
+                _ => FrictionModel::Coulomb,
+            },
+            static_friction: (self.static_friction * other.static_friction).sqrt(),
+            kinetic_friction: (self.kinetic_friction * other.kinetic_friction).sqrt(),
+            weld_on_contact: self.weld_on_contact || other.weld_on_contact,
+            elasticity: (self.elasticity * other.elasticity).sqrt(),
+        }
     }
 }
 
 /// Rigid body
-#[derive(Clone, Default)]
+///
+/// Dynamic body with non-positive mass is static: it has infinite mass, so neither forces nor impulses
+/// change its velocity, and other bodies bounce off it. Negative masses are not supported
+/// and treated the same way. Similarly, non-positive moment of inertia prevents rotation.
+#[derive(Clone)]
 pub struct Body<S: Solver> {
+    pub kind: BodyKind,
     pub mass: f32,
     pub pos: Var<Vec2, S>,
     pub vel: Var<Vec2, S>,
@@ -100,41 +550,194 @@ pub struct Body<S: Solver> {
     pub rot: Var<Rot2, S>,
     /// Angular speed.
     pub asp: Var<f32, S>,
+
+    pub material: Material,
+    /// Gravity multiplier, `0.0` means weightlessness and negative values make body rise.
+    pub gravity_scale: f32,
+    /// Bodies with charges of the same sign repel and of opposite signs attract.
+    pub charge: f32,
+
+    /// Use continuous collision detection to prevent tunneling through walls.
+    ///
+    /// Only circles are supported.
+    pub ccd: bool,
+    /// Body translates but never rotates, forces produce no torque on it.
+    pub lock_rotation: bool,
+    /// Body is pinned at its center and only rotates, forces produce no translation.
+    ///
+    /// Velocities of locked degrees of freedom are zeroed at the beginning of each step.
+    pub lock_translation: bool,
+    /// Collision layer bitmask, bodies collide only if they share at least one layer.
+    ///
+    /// Walls collide with all layers.
+    pub layer: u32,
+    /// Position at the beginning of the last step.
+    pub prev_pos: Vec2,
+    /// Rotation at the beginning of the last step.
+    pub prev_rot: Rot2,
+}
+
+impl<S: Solver> Default for Body<S> {
+    fn default() -> Self {
+        Self {
+            kind: BodyKind::Dynamic,
+            mass: 0.0,
+            pos: Var::default(),
+            vel: Var::default(),
+            inm: 0.0,
+            rot: Var::new(Rot2::default()),
+            asp: Var::default(),
+            material: Material::default(),
+            gravity_scale: 1.0,
+            charge: 0.0,
+            ccd: false,
+            lock_rotation: false,
+            lock_translation: false,
+            layer: 1,
+            prev_pos: Vec2::ZERO,
+            prev_rot: Rot2::default(),
+        }
+    }
 }
 
 impl<S: Solver> Body<S> {
-    fn vel_at(&self, p: Vec2) -> Vec2 {
+    /// Whether the body never moves: it is of [`BodyKind::Static`] kind or a dynamic one with infinite mass.
+    ///
+    /// Kinematic bodies keep moving with their velocity regardless of mass.
+    pub fn is_static(&self) -> bool {
+        match self.kind {
+            BodyKind::Static => true,
+            BodyKind::Dynamic => !self.is_dynamic(),
+            BodyKind::Kinematic => false,
+        }
+    }
+    /// Whether the body is moved by forces.
+    pub fn is_dynamic(&self) -> bool {
+        self.kind == BodyKind::Dynamic && self.mass > 0.0
+    }
+
+    /// Inverse mass, zero for non-dynamic and translation-locked bodies.
+    pub fn inv_mass(&self) -> f32 {
+        if self.is_dynamic() && !self.lock_translation {
+            1.0 / self.mass
+        } else {
+            0.0
+        }
+    }
+    /// Inverse moment of inertia, zero for non-dynamic bodies and bodies that cannot rotate.
+    pub fn inv_inm(&self) -> f32 {
+        if self.is_dynamic() && self.inm > 0.0 && !self.lock_rotation {
+            1.0 / self.inm
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether the body shares a collision layer with `other`.
+    pub fn collides_with(&self, other: &Self) -> bool {
+        self.layer & other.layer != 0
+    }
+
+    /// Instantly place the body at `pos` with rotation `rot`.
+    ///
+    /// Previous pose is reset too, so the jump is neither interpolated nor swept by continuous collision detection.
+    pub fn set_pose(&mut self, pos: Vec2, rot: Rot2) {
+        self.pos = Var::new(pos);
+        self.rot = Var::new(rot);
+        self.prev_pos = pos;
+        self.prev_rot = rot;
+    }
+
+    /// Move the body without affecting its velocity.
+    fn shift(&mut self, delta: Vec2) {
+        self.pos = Var::new(*self.pos + delta);
+    }
+
+    /// Sum of translational and rotational kinetic energy.
+    pub fn kinetic_energy(&self) -> f32 {
+        0.5 * (self.mass * self.vel.length_squared() + self.inm * *self.asp * *self.asp)
+    }
+
+    /// Instantly change momentum by `impulse` applied at world-space `point`.
+    pub fn apply_impulse(&mut self, impulse: Vec2, point: Vec2) {
+        self.vel = Var::new(*self.vel + impulse * self.inv_mass());
+        self.asp = Var::new(*self.asp + torque2(point - *self.pos, impulse) * self.inv_inm());
+    }
+
+    pub(crate) fn vel_at(&self, p: Vec2) -> Vec2 {
         *self.vel + angular_to_linear2(*self.asp, p - *self.pos)
     }
 
     /// Influence item by directed deformation `def` at point of contact `pos` moving with velocity `vel`.
-    pub fn contact(&mut self, actor: &mut impl Actor<S>, def: Vec2, pos: Vec2, vel: Vec2) {
+    ///
+    /// `material` is the combined material of the contacting surfaces.
+    pub fn contact(
+        &mut self,
+        actor: &mut impl Actor<S>,
+        material: &Material,
+        config: &PhysicsConfig,
+        def: Vec2,
+        pos: Vec2,
+        vel: Vec2,
+    ) {
         let vel = self.vel_at(pos) - vel;
 
         let norm = def.normalize_or_zero();
-        // Elastic force (normal reaction)
-        let elast_f = ELAST * def;
+        // Elastic force (normal reaction), weakened while bodies move apart
+        let restitution = config.restitution * material.elasticity;
+        let rebound = if vel.dot(norm) > 0.0 {
+            restitution * restitution
+        } else {
+            1.0
+        };
+        let elast_f = rebound * config.elasticity * def;
 
         // Damping force (parallel to `norm`)
-        let damp_f = -DAMP * vel.dot(norm) * elast_f;
-        // Liquid friction force (perpendicular to `norm`)
-        let frict_f = -FRICT * vel.dot(norm.perp()) * elast_f.perp();
+        let damp_f = -config.damping * vel.dot(norm) * elast_f;
+        // Friction force (perpendicular to `norm`)
+        let tang_vel = vel.dot(norm.perp());
+        let frict_f = match material.friction_model {
+            FrictionModel::Liquid => -config.friction * tang_vel * elast_f.perp(),
+            FrictionModel::Coulomb => {
+                let normal_f = elast_f.length();
+                // Stiff liquid friction while sticking, bounded by static friction
+                let stick_f = -STICK_FRICT * tang_vel * normal_f;
+                let frict = if stick_f.abs() <= material.static_friction * normal_f {
+                    stick_f
+                } else {
+                    -material.kinetic_friction * normal_f * tang_vel.signum()
+                };
+                frict * norm.perp()
+            }
+        };
         // Total force
         let total_f = elast_f + damp_f + frict_f;
+        let total_f = match config.max_contact_force {
+            Some(max) => total_f.clamp_length_max(max),
+            None => total_f,
+        };
 
         actor.apply(self, pos, total_f);
     }
 
-    /// Pin `loc_pos` point in local item coordinates to `target` point in world space.
-    pub fn attract(&mut self, actor: &mut impl Actor<S>, target: Vec2, self_pos: Vec2) {
+    /// Pin `loc_pos` point in local item coordinates to `target` point in world space
+    /// with a spring of `stiffness` and velocity `damping`.
+    pub fn attract(
+        &mut self,
+        actor: &mut impl Actor<S>,
+        stiffness: f32,
+        damping: f32,
+        target: Vec2,
+        self_pos: Vec2,
+    ) {
         let loc_pos = self.rot.transform(self_pos);
         let rel_pos = target - (*self.pos + loc_pos);
         let vel = *self.vel + angular_to_linear2(*self.asp, loc_pos);
 
         // Elastic attraction
-        let elast_f = ELAST * rel_pos;
+        let elast_f = stiffness * rel_pos;
         // Constant damping
-        let damp_f = -MOUSE_DAMP * vel;
+        let damp_f = -damping * vel;
         // Total force
         let total_f = elast_f + damp_f;
 
@@ -142,36 +745,324 @@ impl<S: Solver> Body<S> {
     }
 }
 
-fn contact_wall<S: Solver>(
-    actor: &mut impl Actor<S>,
-    item: &mut Item<S>,
-    offset: f32,
-    normal: Vec2,
-) {
+/// Side of the world box.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WallSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Behavior of a world side.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum BoundaryMode {
+    /// Solid wall.
+    #[default]
+    Closed,
+    /// No wall, items can leave the world.
+    Open,
+}
+
+/// The thing an item is touching.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ContactTarget {
+    /// Other item index.
+    Item(usize),
+    Wall(WallSide),
+}
+
+/// Current contact of an item.
+///
+/// Tag of the other item can be obtained via [`World::item`].
+#[derive(Clone, Copy, Debug)]
+pub struct ContactInfo {
+    pub other: ContactTarget,
+    /// Point of contact in world space.
+    pub point: Vec2,
+    /// Contact normal pointing from the other body towards the item.
+    pub normal: Vec2,
+    /// Overlap area.
+    pub area: f32,
+}
+
+/// Force applied to an item during the derivative pass.
+#[derive(Clone, Copy, Debug)]
+pub struct ContactRecord {
+    /// Item the force is applied to.
+    pub a: usize,
+    /// Item causing the force, `None` for walls and fields.
+    pub b: Option<usize>,
+    /// Point of application in world space.
+    pub point: Vec2,
+    pub force: Vec2,
+}
+
+/// Actor recording forces instead of applying them.
+#[derive(Default)]
+struct RecordActor {
+    current: (usize, Option<usize>),
+    records: Vec<ContactRecord>,
+}
+
+impl<S: Solver> Actor<S> for RecordActor {
+    fn apply(&mut self, _: &mut Body<S>, pos: Vec2, force: Vec2) {
+        let (a, b) = self.current;
+        self.records.push(ContactRecord {
+            a,
+            b,
+            point: pos,
+            force,
+        });
+    }
+    fn interaction(&mut self, item: usize, other: Option<usize>) {
+        self.current = (item, other);
+    }
+}
+
+/// Geometric overlap of two bodies.
+#[derive(Clone, Copy, Debug)]
+pub struct OverlapRegion {
+    /// Always an item.
+    pub a: ContactTarget,
+    pub b: ContactTarget,
+    /// Area of the overlap.
+    pub area: f32,
+    /// Center of mass of the overlap.
+    pub centroid: Vec2,
+}
+
+/// Contact of `(i, j)` pair of `colliders`, if any.
+fn detect_pair(colliders: &[Collider], (i, j): (usize, usize)) -> Option<(usize, usize, Contact)> {
+    colliders[i].detect(&colliders[j]).map(|c| (i, j, c))
+}
+
+/// Narrowphase, contacts of candidate `pairs` in the same order.
+#[cfg(not(feature = "rayon"))]
+fn detect_pairs(colliders: &[Collider], pairs: &[(usize, usize)]) -> Vec<(usize, usize, Contact)> {
+    pairs
+        .iter()
+        .filter_map(|&pair| detect_pair(colliders, pair))
+        .collect()
+}
+/// Narrowphase, contacts of candidate `pairs` in the same order.
+///
+/// Parallel collection preserves the order of pairs, so results don't depend on the thread count.
+#[cfg(feature = "rayon")]
+fn detect_pairs(colliders: &[Collider], pairs: &[(usize, usize)]) -> Vec<(usize, usize, Contact)> {
+    use rayon::prelude::*;
+    pairs
+        .par_iter()
+        .filter_map(|&pair| detect_pair(colliders, pair))
+        .collect()
+}
+
+/// Overlap of two bodies.
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    /// Area of the overlap.
+    pub area: f32,
+    /// Direction from the first body to the second one.
+    pub direction: Vec2,
+    /// Point of application (centroid of the overlap).
+    pub point: Vec2,
+    /// Two points along the contact edge sharing the force instead of [`Self::point`].
+    ///
+    /// Only polygon pairs and polygons touching walls have manifolds,
+    /// it keeps resting edge-to-edge contacts from wobbling.
+    pub manifold: Option<[Vec2; 2]>,
+}
+
+impl Contact {
+    /// Points where the force is applied, shared equally.
+    pub fn points(&self) -> SmallVec<[Vec2; 2]> {
+        match self.manifold {
+            Some(points) => SmallVec::from_buf(points),
+            None => SmallVec::from_slice(&[self.point]),
+        }
+    }
+
+    /// Single contact equivalent to all `contacts` together.
+    fn merge(contacts: impl IntoIterator<Item = Contact>) -> Option<Contact> {
+        let (area, direction, point) = contacts.into_iter().fold(
+            (0.0, Vec2::ZERO, Vec2::ZERO),
+            |(area, direction, point), c| {
+                (
+                    area + c.area,
+                    direction + c.area * c.direction,
+                    point + c.area * c.point,
+                )
+            },
+        );
+        if area > AREA_EPS {
+            Some(Contact {
+                area,
+                direction: direction.normalize_or_zero(),
+                point: point / area,
+                manifold: None,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn detect_wall(collider: &Collider, offset: f32, normal: Vec2) -> Option<Contact> {
+    if let Shape::Compound { .. } = collider.shape {
+        return Contact::merge(
+            collider
+                .parts()
+                .iter()
+                .filter_map(|part| detect_wall(part, offset, normal)),
+        );
+    }
     let wall = HalfPlane { normal, offset };
-    let overlay = match item.geometry() {
+    let overlay = match collider.geometry() {
         Either::Left(left) => left.intersect(&wall).map(|x| x.moment()),
         Either::Right(right) => right
             .intersect_to(&wall)
             .map(|x: Polygon<SmallVec<[Vec2; 5]>>| x.moment()),
+    }?;
+    if overlay.area > AREA_EPS {
+        Some(Contact {
+            area: overlay.area,
+            direction: -normal,
+            point: overlay.centroid,
+            manifold: collider.wall_manifold(offset, normal),
+        })
+    } else {
+        None
+    }
+}
+
+/// Apply force acting on the body as a whole rather than through a contact.
+///
+/// Gravity, fields, fluids, charges and attractors act on dynamic bodies only,
+/// static and kinematic ones don't even receive these forces.
+pub(crate) fn apply_body_force<S: Solver>(
+    actor: &mut impl Actor<S>,
+    body: &mut Body<S>,
+    pos: Vec2,
+    force: Vec2,
+) {
+    if body.is_dynamic() {
+        actor.apply(body, pos, force);
+    }
+}
+
+/// Apply contact force of all touched `walls` as a single contact.
+///
+/// Independent contacts of perpendicular walls near a corner produce conflicting torques,
+/// so forces are summed and applied at the common centroid of overlaps.
+/// Polygon touching a single wall shares the force between the two points of its manifold.
+fn contact_walls<S: Solver>(
+    actor: &mut impl Actor<S>,
+    item: &mut Item<S>,
+    model: ContactModel,
+    config: &PhysicsConfig,
+    wall_material: &Material,
+    walls: &[(WallSide, f32, Vec2)],
+) {
+    let collider = item.collider();
+    let radius = collider.shape.radius();
+    let (mut areas, mut force, mut point) = (SmallVec::<[f32; 4]>::new(), Vec2::ZERO, Vec2::ZERO);
+    let mut manifold = None;
+    for (_, offset, normal) in walls {
+        if let Some(contact) = detect_wall(&collider, *offset, *normal) {
+            areas.push(contact.area);
+            force += model.force(contact.area, radius) * *normal;
+            point += contact.area * contact.point;
+            manifold = contact.manifold;
+        }
+    }
+    let total_area = areas.iter().sum::<f32>();
+    if total_area > AREA_EPS {
+        let points = match (areas.len(), manifold) {
+            (1, Some(manifold)) => SmallVec::from_buf(manifold),
+            _ => SmallVec::<[Vec2; 2]>::from_slice(&[point / total_area]),
+        };
+        let material = item.material.combine(wall_material);
+        item.respond(actor, &material, config, force, &points, &[Vec2::ZERO; 2]);
+    }
+}
+
+/// Overlap of a ring with a circle, direction is from the ring to the circle.
+///
+/// Circle inside of the hole is pushed towards the ring center.
+fn detect_ring_circle(
+    ring_pos: Vec2,
+    outer: f32,
+    inner: f32,
+    pos: Vec2,
+    radius: f32,
+) -> Option<Contact> {
+    let disk = |center, radius| Disk(Circle { center, radius });
+    let circle = disk(pos, radius);
+    let Moment {
+        area: outer_area,
+        centroid: outer_centroid,
+    } = circle.intersect(&disk(ring_pos, outer))?.moment();
+    let (inner_area, inner_centroid) = match circle.intersect(&disk(ring_pos, inner)) {
+        Some(overlay) => {
+            let Moment { area, centroid } = overlay.moment();
+            (area, centroid)
+        }
+        None => (0.0, Vec2::ZERO),
     };
-    if let Some(overlay) = overlay
-        && overlay.area > AREA_EPS
-    {
-        let dir = normal;
-        let force = overlay.area;
-        let poa = overlay.centroid;
-        item.body.contact(actor, dir * force, poa, Vec2::ZERO);
+    let area = outer_area - inner_area;
+    if area <= AREA_EPS {
+        return None;
     }
+    let rel_pos = pos - ring_pos;
+    let direction = if rel_pos.length() >= 0.5 * (outer + inner) {
+        rel_pos
+    } else {
+        -rel_pos
+    };
+    Some(Contact {
+        area,
+        direction,
+        point: (outer_area * outer_centroid - inner_area * inner_centroid) / area,
+        manifold: None,
+    })
 }
 
-impl<S: Solver> Item<S> {
-    pub fn collide(&mut self, other: &mut Self, actor: &mut impl Actor<S>) -> Option<()> {
+/// Estimate penetration depth from overlap area of bodies with specified radii.
+fn penetration(area: f32, radius: f32) -> f32 {
+    area / (2.0 * radius)
+}
+
+impl Collider {
+    fn detect(&self, other: &Self) -> Option<Contact> {
+        if matches!(self.shape, Shape::Compound { .. })
+            || matches!(other.shape, Shape::Compound { .. })
+        {
+            let other_parts = other.parts();
+            return Contact::merge(
+                self.parts()
+                    .iter()
+                    .flat_map(|a| other_parts.iter().filter_map(move |b| a.detect(b))),
+            );
+        }
+        match (&self.shape, &other.shape) {
+            (Shape::Ring { outer, inner }, Shape::Circle { radius }) => {
+                return detect_ring_circle(self.pos, *outer, *inner, other.pos, *radius);
+            }
+            (Shape::Circle { radius }, Shape::Ring { outer, inner }) => {
+                let contact = detect_ring_circle(other.pos, *outer, *inner, self.pos, *radius)?;
+                return Some(Contact {
+                    direction: -contact.direction,
+                    ..contact
+                });
+            }
+            _ => (),
+        }
+        let mut manifold = None;
         let (area, dir, poa) = match (self.geometry(), other.geometry()) {
             (Either::Left(self_circle), Either::Left(other_circle)) => {
                 let overlay = self_circle.intersect(&other_circle)?;
                 let Moment { area, centroid } = overlay.moment();
-                (area, *other.pos - *self.pos, centroid)
+                (area, other.pos - self.pos, centroid)
             }
             (Either::Left(circle), Either::Right(polygon))
             | (Either::Right(polygon), Either::Left(circle)) => {
@@ -187,8 +1078,8 @@ impl<S: Solver> Item<S> {
                 (
                     area,
                     match self.shape {
-                        Shape::Circle { .. } => dir,
-                        Shape::Rectangle { .. } => -dir,
+                        Shape::Circle { .. } | Shape::Ring { .. } => dir,
+                        _ => -dir,
                     },
                     centroid,
                 )
@@ -203,70 +1094,2315 @@ impl<S: Solver> Item<S> {
                     .sum::<Vec2>()
                     .normalize_or_zero()
                     .perp();
+                manifold = self.manifold(other, dir);
                 (area, dir, centroid)
             }
         };
 
         if area > AREA_EPS {
-            let force = area; // .sqrt();
-            self.contact(actor, -force * dir, poa, other.vel_at(poa));
-            other.contact(actor, force * dir, poa, self.vel_at(poa));
-            Some(())
+            Some(Contact {
+                area,
+                direction: dir,
+                point: poa,
+                manifold,
+            })
         } else {
             None
         }
     }
 }
 
-impl<S: Solver> World<S> {
-    pub fn compute_derivs_ext(&mut self, actor: &mut impl Actor<S>) {
-        for item in self.items.iter_mut() {
-            let radius = item.shape.radius();
-            let body = &mut item.body;
+/// Whether `point` is inside of convex polygon with `vertices` of any winding.
+fn contains_point(vertices: &[Vec2], point: Vec2) -> bool {
+    let sides = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| (*b - *a).perp_dot(point - *a));
+    let (mut neg, mut pos) = (false, false);
+    for side in sides {
+        neg |= side < 0.0;
+        pos |= side > 0.0;
+    }
+    !(neg && pos)
+}
 
-            body.pos.deriv += *body.vel;
-            body.rot.deriv += *body.asp;
+impl Collider {
+    /// Two extreme penetrating vertices along the contact tangent, `None` if there is only one.
+    fn manifold(&self, other: &Self, direction: Vec2) -> Option<[Vec2; 2]> {
+        let (self_vertices, other_vertices) = (self.vertices()?, other.vertices()?);
+        let tangent = direction.perp();
+        let penetrating = self_vertices
+            .iter()
+            .filter(|v| contains_point(&other_vertices, **v))
+            .chain(
+                other_vertices
+                    .iter()
+                    .filter(|v| contains_point(&self_vertices, **v)),
+            )
+            .copied()
+            .collect::<SmallVec<[Vec2; 8]>>();
+        let along = |a: &&Vec2, b: &&Vec2| a.dot(tangent).total_cmp(&b.dot(tangent));
+        let min = *penetrating.iter().min_by(along)?;
+        let max = *penetrating.iter().max_by(along)?;
+        if min.distance(max) > 0.0 {
+            Some([min, max])
+        } else {
+            None
+        }
+    }
 
-            // Gravity
-            actor.apply(body, *body.pos, GRAV * body.mass);
+    /// Two extreme vertices behind the wall along its surface, `None` if there is only one.
+    fn wall_manifold(&self, offset: f32, normal: Vec2) -> Option<[Vec2; 2]> {
+        let tangent = normal.perp();
+        let penetrating = self
+            .vertices()?
+            .into_iter()
+            .filter(|v| normal.dot(*v) < offset)
+            .collect::<SmallVec<[Vec2; 4]>>();
+        let along = |a: &&Vec2, b: &&Vec2| a.dot(tangent).total_cmp(&b.dot(tangent));
+        let min = *penetrating.iter().min_by(along)?;
+        let max = *penetrating.iter().max_by(along)?;
+        if min.distance(max) > 0.0 {
+            Some([min, max])
+        } else {
+            None
+        }
+    }
+}
 
-            // Air resistance
-            body.vel.deriv += -(AIRF * radius / body.mass) * *body.vel;
-            body.asp.deriv += -(AIRF * radius / body.inm) * *body.asp;
+/// Overlap of two items, `None` if they don't touch or are on different [layers](Body::layer).
+///
+/// Pure geometry, nothing is mutated.
+pub fn detect_collision<S: Solver>(a: &Item<S>, b: &Item<S>) -> Option<Contact> {
+    if !a.collides_with(b) {
+        return None;
+    }
+    a.collider().detect(&b.collider())
+}
 
-            // Walls
-            let wall_size = self.size - WALL_OFFSET * self.size.min_element();
-            contact_wall(actor, item, -wall_size.x, Vec2::new(1.0, 0.0));
-            contact_wall(actor, item, -wall_size.x, Vec2::new(-1.0, 0.0));
-            contact_wall(actor, item, -wall_size.y, Vec2::new(0.0, 1.0));
-            contact_wall(actor, item, -wall_size.y, Vec2::new(0.0, -1.0));
+impl<S: Solver> Item<S> {
+    /// Apply contact `force` shared by `points` to the item touching other body moving with `other_vels` at them.
+    fn respond(
+        &mut self,
+        actor: &mut impl Actor<S>,
+        material: &Material,
+        config: &PhysicsConfig,
+        force: Vec2,
+        points: &[Vec2],
+        other_vels: &[Vec2],
+    ) {
+        let n = points.len() as f32;
+        let share = force / n;
+        let config = PhysicsConfig {
+            max_contact_force: config.max_contact_force.map(|max| max / n),
+            ..*config
+        };
+        for (point, other_vel) in points.iter().zip(other_vels) {
+            self.contact(actor, material, &config, share, *point, *other_vel);
         }
+    }
 
-        for i in 1..self.items.len() {
-            let (left, other_items) = self.items.split_at_mut(i);
-            let this = left.last_mut().unwrap();
-            for other in other_items {
-                this.collide(other, actor);
-            }
-        }
+    /// Apply contact forces to both items if they overlap.
+    ///
+    /// Pass [`World::contact_model`] and [`World::physics_config`] to get the same forces as the world applies.
+    pub fn collide(
+        &mut self,
+        other: &mut Self,
+        actor: &mut impl Actor<S>,
+        model: ContactModel,
+        config: &PhysicsConfig,
+    ) -> Option<()> {
+        let contact = detect_collision(self, other)?;
+        let material = self.material.combine(&other.material);
+        let radius = self.shape.radius().min(other.shape.radius());
+        let force = model.force(contact.area, radius) * contact.direction;
+        let points = contact.points();
+        let self_vels = points
+            .iter()
+            .map(|p| self.vel_at(*p))
+            .collect::<SmallVec<[Vec2; 2]>>();
+        let other_vels = points
+            .iter()
+            .map(|p| other.vel_at(*p))
+            .collect::<SmallVec<[Vec2; 2]>>();
+        self.respond(actor, &material, config, -force, &points, &other_vels);
+        other.respond(actor, &material, config, force, &points, &self_vels);
+        Some(())
+    }
+}
 
-        if let Some((i, target, loc_pos)) = self.drag {
-            let item = &mut self.items[i];
-            item.body.attract(actor, target, loc_pos);
+impl<S: Solver> World<S> {
+    /// Closed walls as `(side, offset, normal)`.
+    fn walls(&self) -> SmallVec<[(WallSide, f32, Vec2); 4]> {
+        let wall_size = self.wall_size();
+        [
+            (WallSide::Left, -wall_size.x, Vec2::new(1.0, 0.0)),
+            (WallSide::Right, -wall_size.x, Vec2::new(-1.0, 0.0)),
+            (WallSide::Top, -wall_size.y, Vec2::new(0.0, 1.0)),
+            (WallSide::Bottom, -wall_size.y, Vec2::new(0.0, -1.0)),
+        ]
+        .into_iter()
+        .filter(|(side, ..)| self.boundary(*side) == BoundaryMode::Closed)
+        .collect()
+    }
+
+    /// Bounding box `(min, max)` enclosing all items.
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        self.items
+            .iter()
+            .map(Item::aabb)
+            .reduce(|(a_min, a_max), (b_min, b_max)| (a_min.min(b_min), a_max.max(b_max)))
+    }
+
+    /// Mass-weighted mean position of all items.
+    pub fn center_of_mass(&self) -> Option<Vec2> {
+        let mass = self.items.iter().map(|item| item.mass).sum::<f32>();
+        if mass > 0.0 {
+            Some(
+                self.items
+                    .iter()
+                    .map(|item| item.mass * *item.pos)
+                    .sum::<Vec2>()
+                    / mass,
+            )
+        } else {
+            None
         }
     }
-}
 
-impl<S: Solver> System<S> for World<S> {
-    fn compute_derivs(&mut self, _: &S::Context) {
-        self.compute_derivs_ext(&mut DerivActor);
+    /// All forces of the derivative pass, including gravity and drag.
+    ///
+    /// Forces are only recorded, the pass needs mutable access but items are restored afterwards,
+    /// so the world is left unchanged.
+    pub fn collect_contacts(&mut self) -> Vec<ContactRecord> {
+        let bodies = self
+            .items
+            .iter()
+            .map(|item| item.body.clone())
+            .collect::<Vec<_>>();
+        let mut actor = RecordActor::default();
+        self.compute_derivs_ext(&mut actor);
+        for (item, body) in self.items.iter_mut().zip(bodies) {
+            item.body = body;
+        }
+        actor.records
     }
-    fn visit_vars<V: Visitor<S>>(&mut self, visitor: &mut V) {
-        for ent in &mut self.items {
-            visitor.apply(&mut ent.pos);
-            visitor.apply(&mut ent.vel);
-            visitor.apply(&mut ent.rot);
-            visitor.apply(&mut ent.asp);
+
+    /// Current contacts of `i`-th item.
+    ///
+    /// Only detects overlaps, no forces are applied.
+    pub fn contacts_for(&self, i: usize) -> Vec<ContactInfo> {
+        let mut contacts = Vec::new();
+        let Some(item) = self.items.get(i) else {
+            return contacts;
+        };
+        let collider = item.collider();
+        for (side, offset, normal) in self.walls() {
+            if let Some(contact) = detect_wall(&collider, offset, normal) {
+                contacts.push(ContactInfo {
+                    other: ContactTarget::Wall(side),
+                    point: contact.point,
+                    normal: -contact.direction.normalize_or_zero(),
+                    area: contact.area,
+                });
+            }
         }
+        for (j, other) in self.items.iter().enumerate() {
+            if j == i || !item.collides_with(other) {
+                continue;
+            }
+            if let Some(contact) = collider.detect(&other.collider()) {
+                contacts.push(ContactInfo {
+                    other: ContactTarget::Item(j),
+                    point: contact.point,
+                    normal: -contact.direction.normalize_or_zero(),
+                    area: contact.area,
+                });
+            }
+        }
+        contacts
+    }
+
+    /// All current overlaps between items and of items with walls.
+    ///
+    /// Unlike [`Self::contacts_for`] reports plain geometry regardless of applied forces.
+    pub fn overlap_regions(&self) -> Vec<OverlapRegion> {
+        let colliders = self.items.iter().map(Item::collider).collect::<Vec<_>>();
+        let mut regions = Vec::new();
+        for (i, collider) in colliders.iter().enumerate() {
+            for (side, offset, normal) in self.walls() {
+                if let Some(contact) = detect_wall(collider, offset, normal) {
+                    regions.push(OverlapRegion {
+                        a: ContactTarget::Item(i),
+                        b: ContactTarget::Wall(side),
+                        area: contact.area,
+                        centroid: contact.point,
+                    });
+                }
+            }
+            for (j, other) in colliders.iter().enumerate().skip(i + 1) {
+                if let Some(contact) = collider.detect(other) {
+                    regions.push(OverlapRegion {
+                        a: ContactTarget::Item(i),
+                        b: ContactTarget::Item(j),
+                        area: contact.area,
+                        centroid: contact.point,
+                    });
+                }
+            }
+        }
+        regions
+    }
+
+    /// Pairs `(i, j)` of items overlapping by more than `min_area` with `i < j`, edges of the contact graph.
+    ///
+    /// Only detects overlaps using the same broadphase as the simulation, no forces are applied.
+    pub fn contact_graph(&self, min_area: f32) -> Vec<(usize, usize)> {
+        let colliders = self.items.iter().map(Item::collider).collect::<Vec<_>>();
+        self.candidate_pairs(&colliders)
+            .into_iter()
+            .filter(|&(i, j)| {
+                colliders[i]
+                    .detect(&colliders[j])
+                    .is_some_and(|contact| contact.area > min_area)
+            })
+            .collect()
+    }
+
+    /// All current contacts as `(item, other, point, impact_speed)`.
+    ///
+    /// Impact speed is the speed of approach along the contact normal, zero if bodies separate.
+    ///
+    /// Impacts of each item with walls come first, then with items of greater index.
+    pub(crate) fn impacts(&self) -> Vec<(usize, ContactTarget, Vec2, f32)> {
+        let walls = self.walls();
+        let colliders = self.items.iter().map(Item::collider).collect::<Vec<_>>();
+        let pairs = self.candidate_pairs(&colliders);
+        let mut pairs = pairs.iter().peekable();
+        let mut impacts = Vec::new();
+        for (i, collider) in colliders.iter().enumerate() {
+            let item = &self.items[i];
+            for &(side, offset, normal) in &walls {
+                if let Some(contact) = detect_wall(collider, offset, normal) {
+                    let speed = -item.vel_at(contact.point).dot(normal);
+                    impacts.push((i, ContactTarget::Wall(side), contact.point, speed.max(0.0)));
+                }
+            }
+            // Pairs are sorted, so pairs of `i`-th item are next
+            while let Some(&&(_, j)) = pairs.next_if(|(a, _)| *a == i) {
+                if let Some(contact) = collider.detect(&colliders[j]) {
+                    let rel_vel = item.vel_at(contact.point) - self.items[j].vel_at(contact.point);
+                    let speed = rel_vel.dot(contact.direction.normalize_or_zero());
+                    impacts.push((i, ContactTarget::Item(j), contact.point, speed.max(0.0)));
+                }
+            }
+        }
+        impacts
+    }
+
+    /// The deepest overlap found at the end of the last step.
+    pub fn max_penetration(&self) -> f32 {
+        self.max_penetration
+    }
+
+    /// Sum of all overlap areas found at the end of the last step.
+    pub fn total_overlap(&self) -> f32 {
+        self.total_overlap
+    }
+
+    /// Total kinetic energy of all items.
+    pub fn kinetic_energy(&self) -> f32 {
+        self.items.iter().map(|item| item.kinetic_energy()).sum()
+    }
+
+    /// Index of the item with the greatest [kinetic energy](Body::kinetic_energy), `None` if the world is empty.
+    pub fn most_energetic(&self) -> Option<usize> {
+        (0..self.items.len()).max_by(|&a, &b| {
+            let (a, b) = (&self.items[a], &self.items[b]);
+            a.kinetic_energy().total_cmp(&b.kinetic_energy())
+        })
+    }
+
+    /// Whether items are packed so tight that the solver cannot separate them.
+    ///
+    /// True when total overlap exceeds [`JAM_OVERLAP`] of total item area while
+    /// kinetic energy stays above `energy_threshold`.
+    pub fn is_jammed(&self, energy_threshold: f32) -> bool {
+        let area = self.items.iter().map(|item| item.shape.area()).sum::<f32>();
+        area > 0.0
+            && self.total_overlap > JAM_OVERLAP * area
+            && self.kinetic_energy() > energy_threshold
+    }
+
+    /// Broadphase: pairs of items sharing a layer with overlapping bounding boxes, only they may touch.
+    ///
+    /// `colliders` are colliders of all items, pairs `(i, j)` have `i < j` and are sorted.
+    /// Boxes are swept along the x axis, so only pairs overlapping along it are checked.
+    pub(crate) fn candidate_pairs(&self, colliders: &[Collider]) -> Vec<(usize, usize)> {
+        let aabbs = colliders.iter().map(Collider::aabb).collect::<Vec<_>>();
+        let mut order = (0..aabbs.len()).collect::<Vec<_>>();
+        order.sort_unstable_by(|&a, &b| aabbs[a].0.x.total_cmp(&aabbs[b].0.x));
+        let mut pairs = Vec::new();
+        for (k, &a) in order.iter().enumerate() {
+            let (a_min, a_max) = aabbs[a];
+            for &b in &order[(k + 1)..] {
+                let (b_min, b_max) = aabbs[b];
+                if b_min.x > a_max.x {
+                    break;
+                }
+                if a_min.y <= b_max.y
+                    && b_min.y <= a_max.y
+                    && self.items[a].collides_with(&self.items[b])
+                {
+                    pairs.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        // Contacts are applied in the same order regardless of item positions
+        pairs.sort_unstable();
+        pairs
+    }
+
+    pub fn compute_derivs_ext(&mut self, actor: &mut impl Actor<S>) {
+        let walls = self.walls();
+        let drag_model = self.drag_model;
+        let contact_model = self.contact_model;
+        let config = self.config;
+        let field = self.field;
+        let wall_material = self.wall_material;
+        let wall_cushion = self.wall_cushion;
+        let fluids = self
+            .fluids
+            .iter()
+            .map(|fluid| (fluid, fluid.collider()))
+            .collect::<SmallVec<[_; 4]>>();
+        for (i, item) in self.items.iter_mut().enumerate() {
+            actor.interaction(i, None);
+            let radius = item.shape.radius();
+            let half_extent = if wall_cushion > 0.0 {
+                let (min, max) = item.aabb();
+                0.5 * (max - min)
+            } else {
+                Vec2::ZERO
+            };
+            let body = &mut item.body;
+            // Velocities of locked degrees of freedom are zeroed before the step, see `enforce_locks`
+            let fixed = body.is_static();
+            if !fixed && !body.lock_translation {
+                body.pos.deriv += *body.vel;
+            }
+            if !fixed && !body.lock_rotation {
+                body.rot.deriv += *body.asp;
+            }
+
+            // Gravity
+            let gravity = GRAV * body.mass * body.gravity_scale;
+            apply_body_force(actor, body, *body.pos, gravity);
+            // Uniform field
+            if field != Vec2::ZERO {
+                apply_body_force(actor, body, *body.pos, field * body.mass);
+            }
+
+            // Air resistance, vanishes for non-dynamic bodies together with inverse mass
+            body.vel.deriv += match drag_model {
+                DragModel::Linear => -(AIRF * radius * body.inv_mass()) * *body.vel,
+                DragModel::Quadratic => {
+                    -(AIRF2 * radius * body.inv_mass()) * body.vel.length() * *body.vel
+                }
+            };
+            body.asp.deriv += -(AIRF * radius * body.inv_inm()) * *body.asp;
+
+            // Wall cushion
+            if wall_cushion > 0.0 {
+                for &(_, offset, normal) in &walls {
+                    // Gap between the wall and the body extent towards it
+                    let gap = normal.dot(*body.pos) - offset - half_extent.dot(normal.abs());
+                    let depth = (wall_cushion - gap).clamp(0.0, wall_cushion);
+                    if depth > 0.0 {
+                        let force = CUSHION_STIFF * body.mass * depth * normal;
+                        apply_body_force(actor, body, *body.pos, force);
+                    }
+                }
+            }
+
+            // Fluids
+            for (fluid, fluid_collider) in &fluids {
+                if let Some(Contact { area, point, .. }) = item.collider().detect(fluid_collider) {
+                    let body = &mut item.body;
+                    // Buoyancy
+                    apply_body_force(actor, body, point, -GRAV * fluid.density * area);
+                    // Fluid drag
+                    let vel = body.vel_at(point);
+                    apply_body_force(actor, body, point, -FLUID_DRAG * fluid.density * area * vel);
+                }
+            }
+
+            // Walls
+            contact_walls(actor, item, contact_model, &config, &wall_material, &walls);
+        }
+
+        // Detect all contacts first and then apply forces in the same order.
+        #[cfg(feature = "profiling")]
+        let start = std::time::Instant::now();
+        let colliders = self.items.iter().map(Item::collider).collect::<Vec<_>>();
+        let pairs = self.candidate_pairs(&colliders);
+        #[cfg(feature = "profiling")]
+        let start = {
+            self.timings.broadphase += start.elapsed();
+            std::time::Instant::now()
+        };
+        let contacts = detect_pairs(&colliders, &pairs);
+        #[cfg(feature = "profiling")]
+        {
+            self.timings.narrowphase += start.elapsed();
+        }
+        #[cfg(feature = "log")]
+        log::trace!("{} pairs, {} contacts", pairs.len(), contacts.len());
+        for (i, j, contact) in contacts {
+            let radius = colliders[i].shape.radius().min(colliders[j].shape.radius());
+            let material = self.items[i].material.combine(&self.items[j].material);
+            let force = contact_model.force(contact.area, radius) * contact.direction;
+            let points = contact.points();
+            let (self_vels, other_vels) = points
+                .iter()
+                .map(|p| (self.items[i].vel_at(*p), self.items[j].vel_at(*p)))
+                .unzip::<_, _, SmallVec<[Vec2; 2]>, SmallVec<[Vec2; 2]>>();
+            actor.interaction(i, Some(j));
+            self.items[i].respond(actor, &material, &config, -force, &points, &other_vels);
+            actor.interaction(j, Some(i));
+            self.items[j].respond(actor, &material, &config, force, &points, &self_vels);
+        }
+
+        // Electrostatic forces between charged bodies
+        let charged = (0..self.items.len())
+            .filter(|&i| self.items[i].charge != 0.0)
+            .collect::<SmallVec<[usize; 16]>>();
+        for (k, &i) in charged.iter().enumerate() {
+            for &j in &charged[(k + 1)..] {
+                let rel_pos = *self.items[j].pos - *self.items[i].pos;
+                let dist2 = rel_pos.length_squared() + CHARGE_SOFTENING * CHARGE_SOFTENING;
+                let force = COULOMB * self.items[i].charge * self.items[j].charge
+                    / (dist2 * dist2.sqrt())
+                    * rel_pos;
+                let (a_pos, b_pos) = (*self.items[i].pos, *self.items[j].pos);
+                actor.interaction(i, Some(j));
+                apply_body_force(actor, &mut self.items[i].body, a_pos, -force);
+                actor.interaction(j, Some(i));
+                apply_body_force(actor, &mut self.items[j].body, b_pos, force);
+            }
+        }
+
+        self.apply_attractors(actor);
+        self.apply_joints(actor);
+
+        if let Some((i, target, loc_pos)) = self.drag
+            && self.drag_style == DragStyle::Spring
+        {
+            actor.interaction(i, None);
+            let item = &mut self.items[i];
+            item.body.attract(
+                actor,
+                config.drag_stiffness,
+                config.mouse_damping,
+                target,
+                loc_pos,
+            );
+        }
+    }
+}
+
+impl<S: Solver> World<S> {
+    /// Perform a single simulation step of duration `dt`.
+    ///
+    /// `dt` is real time, simulated time is scaled by [`Self::time_scale`] and split into
+    /// sub-steps not longer than [`MAX_SUBSTEP`], so accuracy doesn't depend on time scale.
+    /// At most [`MAX_SUBSTEPS`] sub-steps are performed, the rest of simulated time is dropped.
+    /// Does nothing if simulated time is not positive and finite.
+    pub fn advance(&mut self, solver: &mut S, dt: f32) {
+        let sim_dt = dt * self.time_scale;
+        if !(sim_dt > 0.0 && sim_dt.is_finite()) {
+            return;
+        }
+        let n = (sim_dt / MAX_SUBSTEP).ceil().max(1.0) as usize;
+        if n > MAX_SUBSTEPS {
+            self.solve_fixed_substeps(solver, MAX_SUBSTEPS as f32 * MAX_SUBSTEP, MAX_SUBSTEPS);
+        } else {
+            self.solve_fixed_substeps(solver, sim_dt, n);
+        }
+    }
+
+    /// Split simulated time `dt` into `n` equal sub-steps and perform them.
+    ///
+    /// Unlike [`Self::advance`], neither time scale nor [`MAX_SUBSTEP`] are applied.
+    /// Does nothing if `dt` is not positive and finite or the world is [degenerate](Self::is_degenerate).
+    pub fn solve_fixed_substeps(&mut self, solver: &mut S, dt: f32, n: usize) {
+        if !(dt > 0.0 && dt.is_finite()) || self.is_degenerate() {
+            return;
+        }
+        #[cfg(feature = "profiling")]
+        self.begin_timings();
+        for _ in 0..n {
+            self.substep(solver, dt / n as f32);
+        }
+        #[cfg(feature = "profiling")]
+        self.end_timings();
+    }
+
+    fn substep(&mut self, solver: &mut S, dt: f32) {
+        self.begin_substep();
+        #[cfg(feature = "profiling")]
+        let (start, detection) = (std::time::Instant::now(), self.timings.detection());
+        solver.solve_step(self, dt);
+        self.drag_velocity(dt);
+        #[cfg(feature = "profiling")]
+        let start = {
+            // Contact detection performed during derivative passes is not counted
+            let detection = self.timings.detection() - detection;
+            self.timings.integration += start.elapsed().saturating_sub(detection);
+            std::time::Instant::now()
+        };
+        self.end_substep(dt);
+        #[cfg(feature = "profiling")]
+        {
+            self.timings.post_step += start.elapsed();
+        }
+    }
+
+    /// Prepare items for integration of a sub-step.
+    fn begin_substep(&mut self) {
+        self.enforce_locks();
+        for item in &mut self.items {
+            item.body.prev_pos = *item.body.pos;
+            item.body.prev_rot = *item.body.rot;
+        }
+    }
+
+    /// Everything performed after integration of a sub-step of duration `dt`.
+    fn end_substep(&mut self, dt: f32) {
+        self.clamp_angular_speed();
+        self.resolve_ccd();
+        for _ in 0..self.solver_iterations {
+            self.correct_positions();
+        }
+        self.apply_welds();
+        self.handle_collisions();
+        self.handle_escaped();
+        self.count_step(dt);
+        self.run_spawners();
+        self.update_contact_stats();
+    }
+
+    /// Count contacts and overlaps of the current state, see [`Self::max_penetration`] and [`Item::contacts`].
+    fn update_contact_stats(&mut self) {
+        let walls = self.walls();
+        let colliders = self.items.iter().map(Item::collider).collect::<Vec<_>>();
+        let (mut max_penetration, mut total_overlap) = (0.0f32, 0.0f32);
+        for (item, collider) in self.items.iter_mut().zip(&colliders) {
+            item.contacts = 0;
+            for &(_, offset, normal) in &walls {
+                if let Some(contact) = detect_wall(collider, offset, normal) {
+                    max_penetration =
+                        max_penetration.max(penetration(contact.area, collider.shape.radius()));
+                    total_overlap += contact.area;
+                    item.contacts += 1;
+                }
+            }
+        }
+        let pairs = self.candidate_pairs(&colliders);
+        for (i, j, contact) in detect_pairs(&colliders, &pairs) {
+            let radius = colliders[i].shape.radius().min(colliders[j].shape.radius());
+            max_penetration = max_penetration.max(penetration(contact.area, radius));
+            total_overlap += contact.area;
+            self.items[i].contacts += 1;
+            self.items[j].contacts += 1;
+        }
+        self.max_penetration = max_penetration;
+        self.total_overlap = total_overlap;
+    }
+
+    /// Move dragged item towards the target in [`DragStyle::CriticallyDamped`] style.
+    ///
+    /// The grabbed point covers `1 - exp(-dt / DRAG_TIME)` of the remaining distance per step, so it never overshoots.
+    fn drag_velocity(&mut self, dt: f32) {
+        if self.drag_style != DragStyle::CriticallyDamped || dt <= 0.0 {
+            return;
+        }
+        if let Some((i, target, loc_pos)) = self.drag
+            && self.items[i].inv_mass() > 0.0
+        {
+            let item = &mut self.items[i];
+            let point = *item.pos + item.rot.transform(loc_pos);
+            let vel = (target - point) * (1.0 - (-dt / DRAG_TIME).exp()) / dt;
+            item.vel = Var::new(vel);
+        }
+    }
+
+    /// Account a performed simulation step of duration `dt`.
+    fn count_step(&mut self, dt: f32) {
+        self.step_count += 1;
+        self.elapsed += dt as f64;
+    }
+
+    /// Number of simulation sub-steps performed since creation or last [`Self::clear`].
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+    /// Simulated time in seconds since creation or last [`Self::clear`].
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+    /// Set ratio of simulated time to real time, values below `1.0` give slow motion.
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.max(0.0);
+    }
+
+    /// Fill rectangle between `min` and `max` corners with fluid of `density`.
+    pub fn add_fluid_region(&mut self, min: Vec2, max: Vec2, density: f32) {
+        self.fluids.push(FluidRegion {
+            min: min.min(max),
+            max: min.max(max),
+            density,
+        });
+    }
+    pub fn fluid_regions(&self) -> &[FluidRegion] {
+        &self.fluids
+    }
+    pub fn clear_fluid_regions(&mut self) {
+        self.fluids.clear();
+    }
+
+    pub fn wall_material(&self) -> &Material {
+        &self.wall_material
+    }
+    /// Set material of walls, combined with item material on wall contacts.
+    pub fn set_wall_material(&mut self, material: Material) {
+        self.wall_material = material;
+    }
+
+    pub fn wall_cushion(&self) -> f32 {
+        self.wall_cushion
+    }
+    /// Set thickness of the layer along walls which softly pushes bodies away before they hit the walls.
+    ///
+    /// Repulsion is proportional to the depth of the body in the layer, zero disables it.
+    pub fn set_wall_cushion(&mut self, thickness: f32) {
+        self.wall_cushion = thickness.max(0.0);
+    }
+
+    pub fn field(&self) -> Vec2 {
+        self.field
+    }
+    /// Set constant acceleration applied to all bodies in addition to gravity.
+    pub fn set_field(&mut self, accel: Vec2) {
+        self.field = accel;
+    }
+
+    pub fn physics_config(&self) -> &PhysicsConfig {
+        &self.config
+    }
+    pub fn physics_config_mut(&mut self) -> &mut PhysicsConfig {
+        &mut self.config
+    }
+    pub fn set_physics_config(&mut self, config: PhysicsConfig) {
+        self.config = config;
+    }
+
+    pub fn contact_model(&self) -> ContactModel {
+        self.contact_model
+    }
+    pub fn set_contact_model(&mut self, model: ContactModel) {
+        self.contact_model = model;
+    }
+
+    pub fn drag_model(&self) -> DragModel {
+        self.drag_model
+    }
+    pub fn set_drag_model(&mut self, model: DragModel) {
+        self.drag_model = model;
+    }
+
+    /// Fraction of penetration depth removed after each step by moving bodies apart.
+    pub fn position_correction(&self) -> f32 {
+        self.position_correction
+    }
+    pub fn set_position_correction(&mut self, factor: f32) {
+        self.position_correction = factor.clamp(0.0, 1.0);
+    }
+
+    pub fn solver_iterations(&self) -> usize {
+        self.solver_iterations
+    }
+    /// Set number of position correction passes after each sub-step, at least one.
+    ///
+    /// Contacts are re-detected on each pass, so more passes resolve overlaps in tight stacks
+    /// better at higher CPU cost. Iterations work through [position correction](Self::position_correction),
+    /// so they do nothing while it is zero, see [`ITERATION_CORRECTION`] for a suitable factor.
+    pub fn set_solver_iterations(&mut self, n: usize) {
+        self.solver_iterations = n.max(1);
+    }
+
+    pub fn max_angular_speed(&self) -> Option<f32> {
+        self.max_angular_speed
+    }
+    /// Limit magnitude of angular speed of all items after each step.
+    ///
+    /// Clamping removes rotational energy, so energy is not conserved while the limit is hit.
+    pub fn set_max_angular_speed(&mut self, max: Option<f32>) {
+        self.max_angular_speed = max.map(f32::abs);
+    }
+
+    pub fn max_contact_force(&self) -> Option<f32> {
+        self.config.max_contact_force
+    }
+    /// Limit magnitude of the total force of each contact (elastic, damping and friction),
+    /// so that deeply overlapping items separate firmly but not explosively.
+    ///
+    /// Unlike [position correction](Self::set_position_correction) it bounds forces, not displacements.
+    /// Same as [`PhysicsConfig::max_contact_force`].
+    pub fn set_max_contact_force(&mut self, max: Option<f32>) {
+        self.config.max_contact_force = max.map(f32::abs);
+    }
+
+    /// Zero velocities of static bodies and locked degrees of freedom.
+    ///
+    /// Locked variables are not integrated, so it is done once before a sub-step
+    /// rather than in derivative passes which must not modify the state.
+    fn enforce_locks(&mut self) {
+        for item in &mut self.items {
+            let body = &mut item.body;
+            if body.is_static() || body.lock_translation {
+                body.vel = Var::new(Vec2::ZERO);
+            }
+            if body.is_static() || body.lock_rotation {
+                body.asp = Var::new(0.0);
+            }
+        }
+    }
+
+    fn clamp_angular_speed(&mut self) {
+        let Some(max) = self.max_angular_speed else {
+            return;
+        };
+        for item in &mut self.items {
+            if item.asp.abs() > max {
+                item.asp = Var::new(item.asp.clamp(-max, max));
+            }
+        }
+    }
+
+    /// Move circles that passed through a wall during the last step back to the point of impact.
+    ///
+    /// Normal velocity is reflected with the same restitution as in a regular wall contact.
+    fn resolve_ccd(&mut self) {
+        let walls = self.walls();
+        let (restitution, wall_material) = (self.config.restitution, self.wall_material);
+        for item in &mut self.items {
+            let Shape::Circle { radius } = item.shape else {
+                continue;
+            };
+            if !item.body.ccd {
+                continue;
+            }
+            for &(_, offset, normal) in &walls {
+                // Signed distances from the wall surface to the center
+                let (dist_prev, dist) = (
+                    normal.dot(item.body.prev_pos) - offset,
+                    normal.dot(*item.body.pos) - offset,
+                );
+                // Center passed through the surface, penalty force cannot handle it
+                if dist >= 0.0 || dist_prev <= dist {
+                    continue;
+                }
+                let t = ((dist_prev - radius) / (dist_prev - dist)).clamp(0.0, 1.0);
+                let prev_pos = item.body.prev_pos;
+                item.body.pos = Var::new(prev_pos.lerp(*item.body.pos, t));
+                let normal_vel = item.body.vel.dot(normal);
+                if normal_vel < 0.0 {
+                    let restitution =
+                        restitution * wall_material.combine(&item.material).elasticity;
+                    item.body.vel =
+                        Var::new(*item.body.vel - (1.0 + restitution) * normal_vel * normal);
+                }
+            }
+        }
+    }
+
+    /// Push overlapping bodies apart along contact normals (Baumgarte-like correction).
+    fn correct_positions(&mut self) {
+        if self.position_correction <= 0.0 || self.edit_mode {
+            return;
+        }
+        let factor = self.position_correction;
+        let correction = |area: f32, radius: f32| {
+            factor * (penetration(area, radius) - CORRECTION_SLOP).max(0.0)
+        };
+
+        let walls = self.walls();
+        for item in &mut self.items {
+            // Walls are immovable, so bodies that can't move stay in place
+            if item.inv_mass() == 0.0 {
+                continue;
+            }
+            let collider = item.collider();
+            let radius = collider.shape.radius();
+            for &(_, offset, normal) in &walls {
+                if let Some(contact) = detect_wall(&collider, offset, normal) {
+                    item.body.shift(normal * correction(contact.area, radius));
+                }
+            }
+        }
+
+        let colliders = self.items.iter().map(Item::collider).collect::<Vec<_>>();
+        for (i, j) in self.candidate_pairs(&colliders) {
+            let Some(contact) = colliders[i].detect(&colliders[j]) else {
+                continue;
+            };
+            let radius = colliders[i].shape.radius().min(colliders[j].shape.radius());
+            let delta = correction(contact.area, radius) * contact.direction.normalize_or_zero();
+            // Heavier bodies move less
+            let (inv_i, inv_j) = (self.items[i].inv_mass(), self.items[j].inv_mass());
+            let inv_sum = inv_i + inv_j;
+            if inv_sum > 0.0 {
+                self.items[i].body.shift(-delta * (inv_i / inv_sum));
+                self.items[j].body.shift(delta * (inv_j / inv_sum));
+            }
+        }
+    }
+}
+
+/// Number of state variables per item in flattened state vector.
+pub const ITEM_STATE_LEN: usize = 6;
+
+impl<S: Solver> World<S> {
+    /// Flattened state of all items.
+    ///
+    /// Each item is represented by [`ITEM_STATE_LEN`] values: position, velocity, angle and angular speed.
+    pub fn state_vector(&self) -> Vec<f32> {
+        let mut state = Vec::with_capacity(ITEM_STATE_LEN * self.items.len());
+        for item in &self.items {
+            state.extend_from_slice(&[
+                item.pos.x,
+                item.pos.y,
+                item.vel.x,
+                item.vel.y,
+                item.rot.angle(),
+                *item.asp,
+            ]);
+        }
+        state
+    }
+
+    /// Set state of all items from the flattened vector of the same layout as [`Self::state_vector`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `state` length doesn't match the number of items.
+    pub fn apply_state_vector(&mut self, state: &[f32]) {
+        assert_eq!(
+            state.len(),
+            ITEM_STATE_LEN * self.items.len(),
+            "State vector length mismatch"
+        );
+        for (item, x) in self
+            .items
+            .iter_mut()
+            .zip(state.chunks_exact(ITEM_STATE_LEN))
+        {
+            item.set_pose(Vec2::new(x[0], x[1]), Rot2::from_angle(x[4]));
+            item.vel = Var::new(Vec2::new(x[2], x[3]));
+            item.asp = Var::new(x[5]);
+        }
+    }
+
+    /// Compute derivatives and return them flattened in the same layout as [`Self::state_vector`].
+    pub fn derivative_vector(&mut self) -> Vec<f32> {
+        for item in &mut self.items {
+            item.pos.deriv = Default::default();
+            item.vel.deriv = Default::default();
+            item.rot.deriv = Default::default();
+            item.asp.deriv = Default::default();
+        }
+        self.compute_derivs_ext(&mut DerivActor);
+        let mut deriv = Vec::with_capacity(ITEM_STATE_LEN * self.items.len());
+        for item in &self.items {
+            deriv.extend_from_slice(&[
+                item.pos.deriv.x,
+                item.pos.deriv.y,
+                item.vel.deriv.x,
+                item.vel.deriv.y,
+                item.rot.deriv,
+                item.asp.deriv,
+            ]);
+        }
+        deriv
+    }
+}
+
+/// Runge-Kutta-Fehlberg stage coefficients.
+const RKF_A: [&[f32]; 6] = [
+    &[],
+    &[1.0 / 4.0],
+    &[3.0 / 32.0, 9.0 / 32.0],
+    &[1932.0 / 2197.0, -7200.0 / 2197.0, 7296.0 / 2197.0],
+    &[439.0 / 216.0, -8.0, 3680.0 / 513.0, -845.0 / 4104.0],
+    &[
+        -8.0 / 27.0,
+        2.0,
+        -3544.0 / 2565.0,
+        1859.0 / 4104.0,
+        -11.0 / 40.0,
+    ],
+];
+/// Runge-Kutta-Fehlberg 4th order weights.
+const RKF_B4: [f32; 6] = [
+    25.0 / 216.0,
+    0.0,
+    1408.0 / 2565.0,
+    2197.0 / 4104.0,
+    -1.0 / 5.0,
+    0.0,
+];
+/// Runge-Kutta-Fehlberg 5th order weights.
+const RKF_B5: [f32; 6] = [
+    16.0 / 135.0,
+    0.0,
+    6656.0 / 12825.0,
+    28561.0 / 56430.0,
+    -9.0 / 50.0,
+    2.0 / 55.0,
+];
+
+/// Maximal duration of a single simulation sub-step.
+pub const MAX_SUBSTEP: f32 = 1.0 / 60.0;
+
+/// Maximal number of sub-steps performed by a single [`World::advance`].
+pub const MAX_SUBSTEPS: usize = 600;
+
+/// Minimal adaptive sub-step relative to the whole step.
+const MIN_SUBSTEP: f32 = 1e-4;
+
+/// Statistics of adaptive step.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct AdaptiveStats {
+    /// Number of accepted sub-steps.
+    pub accepted: usize,
+    /// Number of sub-steps rejected due to too large error.
+    pub rejected: usize,
+}
+
+impl<S: Solver> World<S> {
+    /// Which state variables are integrated, in the same layout as [`Self::state_vector`].
+    fn integrated_mask(&self) -> Vec<bool> {
+        let mut mask = Vec::with_capacity(ITEM_STATE_LEN * self.items.len());
+        for i in 0..self.items.len() {
+            let (translation, rotation) = self.integrated(i);
+            mask.extend_from_slice(&[
+                translation,
+                translation,
+                translation,
+                translation,
+                rotation,
+                rotation,
+            ]);
+        }
+        mask
+    }
+
+    /// Advance the world by `dt` using embedded RKF45 method over flattened state.
+    ///
+    /// Sub-step size is adjusted to keep local error estimate below `tol`.
+    /// Each accepted sub-step is processed the same way as in [`Self::advance`]
+    /// (locks, collision resolution, welds, events, spawners), only integration differs.
+    /// Does nothing if the world is [degenerate](Self::is_degenerate) or `dt` is not positive and finite.
+    pub fn solve_adaptive_rkf(&mut self, dt: f32, tol: f32) -> AdaptiveStats {
+        let mut stats = AdaptiveStats::default();
+        if !(dt > 0.0 && dt.is_finite()) || self.is_degenerate() {
+            return stats;
+        }
+        let mut t = 0.0;
+        let mut h = dt;
+        while t < dt {
+            h = h.min(dt - t);
+            self.begin_substep();
+            let y = self.state_vector();
+            let mask = self.integrated_mask();
+            let mut k = Vec::<Vec<f32>>::with_capacity(RKF_A.len());
+            for a in RKF_A {
+                let y_stage = y
+                    .iter()
+                    .enumerate()
+                    .map(|(n, y)| y + h * a.iter().zip(&k).map(|(a, k)| a * k[n]).sum::<f32>())
+                    .collect::<Vec<_>>();
+                self.apply_state_vector(&y_stage);
+                let mut deriv = self.derivative_vector();
+                for (d, &m) in deriv.iter_mut().zip(&mask) {
+                    if !m {
+                        *d = 0.0;
+                    }
+                }
+                k.push(deriv);
+            }
+
+            let mut err = 0.0f32;
+            let y_next = y
+                .iter()
+                .enumerate()
+                .map(|(n, y)| {
+                    let d4 = RKF_B4.iter().zip(&k).map(|(b, k)| b * k[n]).sum::<f32>();
+                    let d5 = RKF_B5.iter().zip(&k).map(|(b, k)| b * k[n]).sum::<f32>();
+                    err = err.max(h * (d5 - d4).abs());
+                    y + h * d5
+                })
+                .collect::<Vec<_>>();
+
+            if err <= tol || h <= MIN_SUBSTEP * dt {
+                self.apply_state_vector(&y_next);
+                self.restore_prev_pose(&y);
+                self.drag_velocity(h);
+                self.end_substep(h);
+                t += h;
+                stats.accepted += 1;
+            } else {
+                self.apply_state_vector(&y);
+                stats.rejected += 1;
+            }
+            let factor = if err > 0.0 {
+                0.9 * (tol / err).powf(0.2)
+            } else {
+                5.0
+            };
+            h *= factor.clamp(0.2, 5.0);
+        }
+        stats
+    }
+
+    /// Set previous poses from the flattened state taken before the sub-step.
+    fn restore_prev_pose(&mut self, state: &[f32]) {
+        for (item, x) in self
+            .items
+            .iter_mut()
+            .zip(state.chunks_exact(ITEM_STATE_LEN))
+        {
+            item.body.prev_pos = Vec2::new(x[0], x[1]);
+            item.body.prev_rot = Rot2::from_angle(x[4]);
+        }
+    }
+
+    /// Whether translation and rotation of `i`-th item are integrated.
+    ///
+    /// Static items are never integrated, in edit mode only the dragged item is.
+    fn integrated(&self, i: usize) -> (bool, bool) {
+        let frozen = match (self.edit_mode, self.drag) {
+            (false, _) => false,
+            (true, Some((j, ..))) => j != i,
+            (true, None) => true,
+        };
+        // Kinematic bodies are integrated too, their velocity derivatives are always zero
+        let item = &self.items[i];
+        if frozen || item.is_static() {
+            return (false, false);
+        }
+        (!item.lock_translation, !item.lock_rotation)
+    }
+}
+
+impl<S: Solver> System<S> for World<S> {
+    fn compute_derivs(&mut self, _: &S::Context) {
+        self.compute_derivs_ext(&mut DerivActor);
+    }
+    fn visit_vars<V: Visitor<S>>(&mut self, visitor: &mut V) {
+        for i in 0..self.items.len() {
+            let (translation, rotation) = self.integrated(i);
+            let ent = &mut self.items[i];
+            if translation {
+                visitor.apply(&mut ent.pos);
+                visitor.apply(&mut ent.vel);
+            }
+            if rotation {
+                visitor.apply(&mut ent.rot);
+                visitor.apply(&mut ent.asp);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phy::Rk4;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn ball(radius: f32, pos: Vec2) -> Item<Rk4> {
+        Item::new(Shape::Circle { radius }, pos, Rgb::new(1.0, 1.0, 1.0))
+    }
+    fn square(half: f32, pos: Vec2) -> Item<Rk4> {
+        Item::new(
+            Shape::Rectangle {
+                size: Vec2::splat(half),
+            },
+            pos,
+            Rgb::new(1.0, 1.0, 1.0),
+        )
+    }
+    fn run(world: &mut World<Rk4>, seconds: f32) {
+        for _ in 0..(seconds / DT).round() as usize {
+            world.advance(&mut Rk4, DT);
+        }
+    }
+
+    #[test]
+    fn zero_mass_is_static() {
+        let mut item = ball(0.1, Vec2::ZERO);
+        item.mass = 0.0;
+        item.inm = 1.0;
+        assert!(item.is_static());
+        assert!(!item.is_dynamic());
+        assert_eq!(item.inv_mass(), 0.0);
+        assert_eq!(item.inv_inm(), 0.0);
+
+        item.apply_impulse(Vec2::new(1.0, 0.0), Vec2::new(0.0, 0.1));
+        assert_eq!(*item.vel, Vec2::ZERO);
+        assert_eq!(*item.asp, 0.0);
+    }
+
+    #[test]
+    fn off_center_impulse_spins_body() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.insert_item(ball(0.1, Vec2::ZERO));
+        let impulse = Vec2::new(0.2, 0.0);
+        assert!(world.impulse_item(0, impulse, Vec2::ZERO));
+        let item = world.item(0).unwrap();
+        assert!((*item.vel - impulse / item.mass).length() < 1e-6);
+        assert_eq!(*item.asp, 0.0);
+
+        assert!(world.impulse_item(0, impulse, Vec2::new(0.0, 0.05)));
+        let item = world.item(0).unwrap();
+        assert!((*item.vel - 2.0 * impulse / item.mass).length() < 1e-6);
+        let asp = torque2(Vec2::new(0.0, 0.05), impulse) / item.inm;
+        assert!(asp != 0.0 && (*item.asp - asp).abs() < 1e-5 * asp.abs());
+        assert!(!world.impulse_item(1, impulse, Vec2::ZERO));
+    }
+
+    #[test]
+    fn zero_mass_body_stays_in_place() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let mut item = ball(0.1, Vec2::ZERO);
+        item.mass = 0.0;
+        // Stale velocities are not integrated
+        item.vel = Var::new(Vec2::new(1.0, 0.0));
+        item.asp = Var::new(1.0);
+        world.insert_item(item);
+        run(&mut world, 0.5);
+
+        let item = world.item(0).unwrap();
+        assert_eq!(*item.pos, Vec2::ZERO);
+        assert_eq!(item.rot.angle(), 0.0);
+        assert_eq!(*item.vel, Vec2::ZERO);
+    }
+
+    #[test]
+    fn ball_rests_on_zero_mass_body() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let floor_pos = Vec2::new(0.0, 0.3);
+        let mut floor = Item::new(
+            Shape::Rectangle {
+                size: Vec2::new(0.5, 0.1),
+            },
+            floor_pos,
+            Rgb::new(1.0, 1.0, 1.0),
+        );
+        floor.mass = 0.0;
+        world.insert_item(floor);
+        world.insert_item(ball(0.1, Vec2::new(0.1, -0.2)));
+        run(&mut world, 4.0);
+
+        let floor = world.item(0).unwrap();
+        assert!((*floor.pos - floor_pos).length() < 1e-6);
+        assert!(floor.rot.angle().abs() < 1e-6);
+        let ball = world.item(1).unwrap();
+        assert!(ball.pos.is_finite() && ball.vel.is_finite());
+        // Ball lies on top of the floor
+        assert!(ball.pos.y < floor_pos.y - 0.1);
+        assert!(ball.pos.y > floor_pos.y - 0.3);
+    }
+
+    #[test]
+    fn overlapping_squares_separate_at_bounded_speed() {
+        let max_force = 0.5;
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_max_contact_force(Some(max_force));
+        // Exactly coincident squares have no separation direction
+        for pos in [Vec2::ZERO, Vec2::new(0.01, 0.0)] {
+            let mut item = square(0.1, pos);
+            item.gravity_scale = 0.0;
+            world.insert_item(item);
+        }
+        let mass = world.item(0).unwrap().mass;
+
+        let mut time = 0.0;
+        for _ in 0..12 {
+            world.advance(&mut Rk4, DT);
+            time += DT;
+            for item in world.items() {
+                assert!(item.vel.length() <= max_force / mass * time * 1.01);
+            }
+        }
+        let (a, b) = (world.item(0).unwrap(), world.item(1).unwrap());
+        assert!(a.pos.distance(*b.pos) > 0.01);
+    }
+
+    /// Height of the top box of a settled stack of `count` boxes.
+    fn stack_top(count: usize, iterations: usize) -> (f32, f32) {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_position_correction(ITERATION_CORRECTION);
+        world.set_solver_iterations(iterations);
+        let floor = world.wall_size().y;
+        for k in 0..count {
+            world.insert_item(square(0.05, Vec2::new(0.0, floor - 0.05 - 0.1 * k as f32)));
+        }
+        run(&mut world, 3.0);
+        (world.item(count - 1).unwrap().pos.y, world.total_overlap())
+    }
+
+    #[test]
+    fn iterations_keep_correction_disabled() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_solver_iterations(8);
+        assert_eq!(world.solver_iterations(), 8);
+        assert_eq!(world.position_correction(), 0.0);
+    }
+
+    #[test]
+    fn more_iterations_make_stack_rigid() {
+        let (soft_top, soft_overlap) = stack_top(5, 1);
+        let (rigid_top, rigid_overlap) = stack_top(5, 8);
+        // Y axis points down
+        assert!(rigid_top < soft_top);
+        assert!(rigid_overlap < soft_overlap);
+    }
+
+    #[test]
+    fn static_body_receives_no_body_forces() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_field(Vec2::new(1.0, 0.0));
+        world.add_fluid_region(Vec2::new(-1.0, 0.0), Vec2::ONE, 1.0);
+        world.add_attractor(Vec2::new(-0.5, 0.0), 1.0, crate::Falloff::Constant);
+        for (x, kind) in [(0.0, BodyKind::Static), (0.3, BodyKind::Dynamic)] {
+            let mut item = ball(0.1, Vec2::new(x, 0.05));
+            item.kind = kind;
+            item.charge = 1.0;
+            world.insert_item(item);
+        }
+
+        let derivs = world.derivative_vector();
+        assert!(derivs[..ITEM_STATE_LEN].iter().all(|&d| d == 0.0));
+        assert!(derivs[ITEM_STATE_LEN..].iter().any(|&d| d != 0.0));
+
+        /// Counts forces applied to static bodies.
+        struct StaticForces(usize);
+        impl<S: Solver> Actor<S> for StaticForces {
+            fn apply(&mut self, body: &mut Body<S>, _: Vec2, _: Vec2) {
+                if body.kind == BodyKind::Static {
+                    self.0 += 1;
+                }
+            }
+        }
+        let mut actor = StaticForces(0);
+        world.compute_derivs_ext(&mut actor);
+        assert_eq!(actor.0, 0);
+    }
+
+    #[test]
+    fn derivative_pass_keeps_locked_state() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let mut item = ball(0.1, Vec2::ZERO);
+        item.lock_translation = true;
+        item.vel = Var::new(Vec2::new(1.0, 0.0));
+        world.insert_item(item);
+
+        let state = world.state_vector();
+        let derivs = world.derivative_vector();
+        assert_eq!(world.state_vector(), state);
+        assert_eq!(&derivs[..4], &[0.0; 4]);
+
+        // Tangential impulse spins the body in place
+        world.impulse_item(0, Vec2::new(0.0, 0.1), Vec2::new(0.1, 0.0));
+        run(&mut world, 0.5);
+        let item = world.item(0).unwrap();
+        assert_eq!(*item.pos, Vec2::ZERO);
+        assert_eq!(*item.vel, Vec2::ZERO);
+        assert!(*item.asp > 0.0);
+    }
+
+    #[test]
+    fn box_on_floor_has_two_contact_points() {
+        let floor = 0.95;
+        let collider = Collider {
+            shape: Shape::Rectangle {
+                size: Vec2::splat(0.1),
+            },
+            pos: Vec2::new(0.2, floor - 0.09),
+            angle: 0.0,
+        };
+        let contact = detect_wall(&collider, -floor, Vec2::new(0.0, -1.0)).unwrap();
+        let [a, b] = contact.manifold.unwrap();
+        let (a, b) = if a.x < b.x { (a, b) } else { (b, a) };
+        assert!(a.distance(Vec2::new(0.1, floor + 0.01)) < 1e-5);
+        assert!(b.distance(Vec2::new(0.3, floor + 0.01)) < 1e-5);
+    }
+
+    #[test]
+    fn box_rests_flat_on_floor() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let floor = world.wall_size().y;
+        world.insert_item(square(0.1, Vec2::new(0.0, floor - 0.1)));
+        run(&mut world, 2.0);
+        let mut max_asp = 0.0f32;
+        for _ in 0..60 {
+            world.advance(&mut Rk4, DT);
+            max_asp = max_asp.max(world.item(0).unwrap().asp.abs());
+        }
+        assert!(max_asp < 1e-3, "{max_asp}");
+        assert!(world.item(0).unwrap().rot.angle().abs() < 1e-3);
+    }
+
+    #[test]
+    fn box_settles_in_corner() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        // Field presses the box into the side wall as gravity does into the floor
+        world.set_field(Vec2::new(4.0, 0.0));
+        let corner = world.wall_size() - 0.1;
+        let mut item = square(0.1, corner);
+        item.set_pose(corner, Rot2::from_angle(0.1));
+        world.insert_item(item);
+        run(&mut world, 3.0);
+        let mut max_asp = 0.0f32;
+        for _ in 0..60 {
+            world.advance(&mut Rk4, DT);
+            max_asp = max_asp.max(world.item(0).unwrap().asp.abs());
+        }
+        assert!(max_asp < 1e-3, "{max_asp}");
+        assert!(world.item(0).unwrap().rot.angle().abs() < 1e-2);
+    }
+
+    /// Maximal height the ball reaches after the first bounce off the floor, relative to the resting position.
+    fn bounce_height(world: &mut World<Rk4>) -> f32 {
+        let radius = 0.05;
+        let rest = world.wall_size().y - radius;
+        world.insert_item(ball(radius, Vec2::new(0.0, rest - 0.5)));
+        let mut impact = false;
+        let mut top = rest;
+        for _ in 0..(2.0 / DT) as usize {
+            world.advance(&mut Rk4, DT);
+            let item = world.item(0).unwrap();
+            if !impact {
+                impact = item.vel.y < 0.0;
+            } else {
+                top = top.min(item.pos.y);
+            }
+        }
+        assert!(impact);
+        rest - top
+    }
+
+    #[test]
+    fn zero_restitution_does_not_bounce() {
+        let mut lively = World::<Rk4>::new(Vec2::ONE);
+        let mut dead = World::<Rk4>::new(Vec2::ONE);
+        dead.physics_config_mut().restitution = 0.0;
+        let (lively, dead) = (bounce_height(&mut lively), bounce_height(&mut dead));
+        assert!(lively > 0.05, "{lively}");
+        assert!(dead < 0.01, "{dead}");
+    }
+
+    #[test]
+    fn ball_bounces_higher_off_elastic_wall() {
+        let mut bouncy = World::<Rk4>::new(Vec2::ONE);
+        bouncy.set_wall_material(Material {
+            elasticity: 1.0,
+            ..Material::default()
+        });
+        let mut dead = World::<Rk4>::new(Vec2::ONE);
+        dead.set_wall_material(Material {
+            elasticity: 0.0,
+            ..Material::default()
+        });
+        let (bouncy, dead) = (bounce_height(&mut bouncy), bounce_height(&mut dead));
+        assert!(bouncy > 0.05, "{bouncy}");
+        assert!(dead < 0.5 * bouncy, "{dead} >= {bouncy} / 2");
+    }
+
+    /// Maximal angle between directions of adjacent edges of a closed outline.
+    fn max_turn(points: &[Vec2]) -> f32 {
+        let edges = (0..points.len())
+            .map(|i| points[(i + 1) % points.len()] - points[i])
+            .collect::<Vec<_>>();
+        (0..edges.len())
+            .map(|i| edges[i].angle_to(edges[(i + 1) % edges.len()]).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn rounded_rectangle_outline() {
+        let size = Vec2::new(0.2, 0.1);
+        let sharp = rounded_rectangle_points(size, 0.0);
+        assert_eq!(sharp.len(), 4);
+        for v in &sharp {
+            assert_eq!(v.abs(), size);
+        }
+        // Adjacent arcs of short sides meet
+        let round = rounded_rectangle_points(size, 0.1);
+        assert_eq!(round.len(), 4 * (CORNER_SEGMENTS + 1) - 2);
+        assert!((0..round.len()).all(|i| round[i] != round[(i + 1) % round.len()]));
+
+        // Contact normal turns gradually along rounded corners instead of flipping at once
+        let rounded = rounded_rectangle_points(size, 0.05);
+        assert!((max_turn(&sharp) - 0.5 * PI).abs() < 1e-4);
+        assert!(max_turn(&rounded) < 0.5 * PI / CORNER_SEGMENTS as f32 + 1e-4);
+    }
+
+    #[test]
+    fn rounded_corner_contact_is_smooth() {
+        // Box is rotated around its center from flat to corner-first contact with the floor
+        let floor = 0.95;
+        let force_steps = |shape: Shape| {
+            let forces = (0..=100)
+                .map(|k| {
+                    let collider = Collider {
+                        shape: shape.clone(),
+                        pos: Vec2::new(0.0, floor - 0.1),
+                        angle: 0.5 * PI * k as f32 / 100.0,
+                    };
+                    detect_wall(&collider, -floor, Vec2::new(0.0, -1.0))
+                        .map_or(0.0, |contact| contact.area)
+                })
+                .collect::<Vec<_>>();
+            forces
+                .windows(2)
+                .map(|w| (w[1] - w[0]).abs())
+                .fold(0.0, f32::max)
+        };
+        let size = Vec2::splat(0.1);
+        let sharp = force_steps(Shape::Rectangle { size });
+        let rounded = force_steps(Shape::RoundedRectangle {
+            size,
+            corner_radius: 0.03,
+        });
+        assert!(rounded < sharp, "{rounded} >= {sharp}");
+    }
+
+    #[test]
+    fn stack_contact_graph_is_chain() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for y in [0.5, 0.31, 0.12] {
+            world.insert_item(square(0.1, Vec2::new(0.0, y)));
+        }
+        // Barely touches the bottom box
+        world.insert_item(square(0.1, Vec2::new(0.1999, 0.6)));
+        assert_eq!(world.contact_graph(1e-4), vec![(0, 1), (1, 2)]);
+        assert_eq!(world.contact_graph(0.0).len(), 3);
+    }
+
+    #[test]
+    fn broadphase_matches_all_pairs() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for _ in 0..40 {
+            world.spawn_random();
+        }
+        world.item_mut(3).unwrap().layer = 2;
+        let colliders = world.items().map(Item::collider).collect::<Vec<_>>();
+        let aabbs = colliders.iter().map(Collider::aabb).collect::<Vec<_>>();
+        let expected = (0..colliders.len())
+            .flat_map(|i| ((i + 1)..colliders.len()).map(move |j| (i, j)))
+            .filter(|&(i, j)| world.items[i].collides_with(&world.items[j]))
+            .filter(|&(i, j)| {
+                let ((a_min, a_max), (b_min, b_max)) = (aabbs[i], aabbs[j]);
+                a_min.cmple(b_max).all() && b_min.cmple(a_max).all()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(world.candidate_pairs(&colliders), expected);
+    }
+
+    #[test]
+    fn narrowphase_matches_serial() {
+        let mut world = World::<Rk4>::new(Vec2::new(0.5, 0.5));
+        for _ in 0..60 {
+            world.spawn_random();
+        }
+        let colliders = world.items().map(Item::collider).collect::<Vec<_>>();
+        let pairs = world.candidate_pairs(&colliders);
+        let serial = pairs
+            .iter()
+            .filter_map(|&pair| detect_pair(&colliders, pair))
+            .collect::<Vec<_>>();
+        let contacts = detect_pairs(&colliders, &pairs);
+        assert!(!contacts.is_empty());
+        assert_eq!(contacts.len(), serial.len());
+        for ((i, j, a), (k, l, b)) in contacts.iter().zip(&serial) {
+            assert_eq!((i, j), (k, l));
+            assert!((a.area - b.area).abs() <= 1e-6 * b.area);
+            assert!(a.direction.distance(b.direction) < 1e-6);
+            assert!(a.point.distance(b.point) < 1e-6);
+        }
+    }
+
+    #[test]
+    fn batched_contacts_match_pairwise_collisions() {
+        let mut world = World::<Rk4>::new(Vec2::new(0.5, 0.5));
+        world.set_contact_model(ContactModel::PenetrationDepth);
+        world.physics_config_mut().restitution = 0.5;
+        for _ in 0..30 {
+            world.spawn_random();
+        }
+        run(&mut world, 0.2);
+        let (model, config) = (world.contact_model(), *world.physics_config());
+
+        let mut expected = RecordActor::default();
+        for i in 0..world.items.len() {
+            for j in (i + 1)..world.items.len() {
+                let (mut a, mut b) = (world.items[i].clone(), world.items[j].clone());
+                a.collide(&mut b, &mut expected, model, &config);
+            }
+        }
+        let records = world
+            .collect_contacts()
+            .into_iter()
+            .filter(|r| r.b.is_some())
+            .collect::<Vec<_>>();
+        assert!(!records.is_empty());
+        assert_eq!(records.len(), expected.records.len());
+        for (r, e) in records.iter().zip(&expected.records) {
+            assert_eq!((r.point, r.force), (e.point, e.force));
+        }
+    }
+
+    #[test]
+    fn ccd_ball_bounces_off_wall() {
+        for ccd in [false, true] {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            let mut item = ball(0.01, Vec2::new(0.5, 0.0));
+            item.gravity_scale = 0.0;
+            item.ccd = ccd;
+            item.vel = Var::new(Vec2::new(60.0, 0.0));
+            world.insert_item(item);
+            world.advance(&mut Rk4, DT);
+
+            let item = world.item(0).unwrap();
+            if ccd {
+                assert!(item.pos.x < world.wall_size().x, "{:?}", *item.pos);
+                assert!(item.vel.x < 0.0);
+            } else {
+                // Tunnels through the wall in a single step
+                assert!(item.pos.x > world.wall_size().x);
+            }
+        }
+    }
+
+    #[test]
+    fn ccd_bounce_uses_restitution() {
+        let bounce = |restitution: f32| {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            world.physics_config_mut().restitution = restitution;
+            let mut item = ball(0.01, Vec2::new(0.5, 0.0));
+            item.gravity_scale = 0.0;
+            item.ccd = true;
+            item.vel = Var::new(Vec2::new(60.0, 0.0));
+            world.insert_item(item);
+            world.advance(&mut Rk4, DT);
+            -world.item(0).unwrap().vel.x
+        };
+        let (elastic, inelastic) = (bounce(1.0), bounce(0.0));
+        assert!(elastic > 30.0, "{elastic}");
+        assert!(inelastic.abs() < 0.1 * elastic, "{inelastic}");
+    }
+
+    #[test]
+    fn overpacked_box_is_jammed() {
+        let mut world = World::<Rk4>::new(Vec2::splat(0.3));
+        // Grid of balls closer than their diameter
+        for i in 0..4 {
+            for j in 0..4 {
+                let pos = 0.125 * Vec2::new(i as f32 - 1.5, j as f32 - 1.5);
+                world.insert_item(ball(0.1, pos));
+            }
+        }
+        run(&mut world, 0.2);
+        assert!(world.is_jammed(1e-3));
+
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.insert_item(ball(0.1, Vec2::new(-0.5, 0.0)));
+        world.insert_item(ball(0.1, Vec2::new(0.5, 0.0)));
+        run(&mut world, 0.2);
+        assert!(!world.is_jammed(1e-3));
+    }
+
+    #[test]
+    fn detect_collision_of_all_shape_pairs() {
+        let part = |x: f32| CompoundPart {
+            shape: Shape::Circle { radius: 0.05 },
+            offset: Vec2::new(x, 0.0),
+            angle: 0.0,
+            mass: 1.0,
+            inm: 1.0,
+            texture: None,
+            color: Rgb::new(1.0, 1.0, 1.0),
+        };
+        // All shapes reach 0.1 from the center along the x axis
+        let shapes = [
+            Shape::Circle { radius: 0.1 },
+            Shape::Rectangle {
+                size: Vec2::splat(0.1),
+            },
+            Shape::RoundedRectangle {
+                size: Vec2::splat(0.1),
+                corner_radius: 0.03,
+            },
+            Shape::Ring {
+                outer: 0.1,
+                inner: 0.05,
+            },
+            Shape::Compound {
+                parts: vec![part(-0.05), part(0.05)],
+            },
+        ];
+        let item = |shape: &Shape, x: f32| {
+            Item::<Rk4>::new(shape.clone(), Vec2::new(x, 0.0), Rgb::new(1.0, 1.0, 1.0))
+        };
+        for a in &shapes {
+            for b in &shapes {
+                let contact = detect_collision(&item(a, 0.0), &item(b, 0.15))
+                    .unwrap_or_else(|| panic!("{a:?} and {b:?} must touch"));
+                assert!(contact.area > 0.0, "{a:?}, {b:?}");
+                assert!(contact.direction.x > 0.0, "{a:?}, {b:?}");
+                assert!((0.0..=0.15).contains(&contact.point.x), "{a:?}, {b:?}");
+
+                assert!(detect_collision(&item(a, 0.0), &item(b, 0.3)).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn rotation_lock_keeps_angle() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for lock in [false, true] {
+            let mut item = square(0.1, Vec2::new(if lock { 0.5 } else { -0.5 }, 0.0));
+            item.gravity_scale = 0.0;
+            item.lock_rotation = lock;
+            world.insert_item(item);
+        }
+        for i in 0..2 {
+            // Off-center impulse
+            let pos = *world.item(i).unwrap().pos + Vec2::new(0.08, 0.0);
+            world.impulse_item(i, Vec2::new(0.0, -0.01), pos);
+        }
+        run(&mut world, 0.2);
+
+        let (free, locked) = (world.item(0).unwrap(), world.item(1).unwrap());
+        assert!(free.rot.angle().abs() > 1e-3);
+        assert!(free.pos.y < -1e-3);
+        assert_eq!(locked.rot.angle(), 0.0);
+        assert_eq!(*locked.asp, 0.0);
+        assert!((locked.pos.y - free.pos.y).abs() < 1e-5);
+    }
+
+    #[test]
+    fn critically_damped_drag_converges_at_large_dt() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let mut item = ball(0.1, Vec2::new(-0.5, 0.0));
+        item.gravity_scale = 0.0;
+        world.insert_item(item);
+        world.set_drag_style(DragStyle::CriticallyDamped);
+        world.drag_acquire(Vec2::new(-0.5, 0.0));
+        let target = Vec2::new(0.5, 0.0);
+        world.drag_move(target);
+
+        let mut dist = f32::INFINITY;
+        for _ in 0..20 {
+            world.solve_fixed_substeps(&mut Rk4, 0.2, 1);
+            let pos = *world.item(0).unwrap().pos;
+            assert!(pos.x <= target.x, "overshoot to {pos:?}");
+            let new_dist = pos.distance(target);
+            assert!(new_dist <= dist);
+            dist = new_dist;
+        }
+        assert!(dist < 1e-3);
+    }
+
+    #[test]
+    fn most_energetic_is_the_fastest() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        assert_eq!(world.most_energetic(), None);
+        for (x, speed) in [(-0.5, 2.0), (0.5, 1.0)] {
+            let mut item = ball(0.1, Vec2::new(x, 0.0));
+            item.vel = Var::new(Vec2::new(0.0, speed));
+            world.insert_item(item);
+        }
+        let energies = world
+            .items()
+            .map(|item| item.kinetic_energy())
+            .collect::<Vec<_>>();
+        assert!((energies[0] - 4.0 * energies[1]).abs() < 1e-6 * energies[0]);
+        assert_eq!(world.most_energetic(), Some(0));
+    }
+
+    #[test]
+    fn kinematic_platform_pushes_ball() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let mut platform = square(0.1, Vec2::new(-0.6, 0.0));
+        platform.kind = BodyKind::Kinematic;
+        platform.vel = Var::new(Vec2::new(0.5, 0.0));
+        world.insert_item(platform);
+        let mut item = ball(0.05, Vec2::new(-0.45, 0.0));
+        item.gravity_scale = 0.0;
+        world.insert_item(item);
+
+        run(&mut world, 1.0);
+        let (platform, item) = (world.item(0).unwrap(), world.item(1).unwrap());
+        assert_eq!(*platform.vel, Vec2::new(0.5, 0.0));
+        assert!((platform.pos.x + 0.1).abs() < 1e-3);
+        assert_eq!(platform.rot.angle(), 0.0);
+        // Ball is kept ahead of the platform
+        assert!(item.pos.x > platform.pos.x + 0.1);
+        assert!(item.vel.x > 0.2);
+    }
+
+    #[test]
+    fn aabb_of_shapes() {
+        let center = Vec2::new(0.2, -0.1);
+        let assert_aabb = |item: &Item<Rk4>, half: Vec2| {
+            let (min, max) = item.aabb();
+            assert!(min.distance(center - half) < 1e-5, "{min:?}");
+            assert!(max.distance(center + half) < 1e-5, "{max:?}");
+        };
+
+        assert_aabb(&ball(0.1, center), Vec2::splat(0.1));
+
+        let mut item = Item::new(
+            Shape::Rectangle {
+                size: Vec2::new(0.2, 0.1),
+            },
+            center,
+            Rgb::new(1.0, 1.0, 1.0),
+        );
+        assert_aabb(&item, Vec2::new(0.2, 0.1));
+        item.set_pose(center, Rot2::from_angle(0.5 * PI));
+        assert_aabb(&item, Vec2::new(0.1, 0.2));
+
+        // Rotated square gets larger by the diagonal
+        let mut item = square(0.1, center);
+        item.set_pose(center, Rot2::from_angle(0.25 * PI));
+        assert_aabb(&item, Vec2::splat(0.1 * 2.0_f32.sqrt()));
+    }
+
+    #[test]
+    fn cushion_decelerates_ball() {
+        let world = |cushion: f32| {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            world.set_wall_cushion(cushion);
+            let mut item = ball(0.05, Vec2::new(0.2, 0.0));
+            item.gravity_scale = 0.0;
+            item.vel = Var::new(Vec2::new(1.0, 0.0));
+            world.insert_item(item);
+            world
+        };
+        let (mut free, mut cushioned) = (world(0.0), world(0.3));
+        // Cushion starts when the ball edge passes 0.65
+        let entry = world(0.0).wall_size().x - 0.3 - 0.05;
+        let mut prev_vel = f32::INFINITY;
+        let (mut entered, mut decelerated) = (false, false);
+        for _ in 0..60 {
+            free.advance(&mut Rk4, DT);
+            cushioned.advance(&mut Rk4, DT);
+            let (a, b) = (free.item(0).unwrap(), cushioned.item(0).unwrap());
+            entered |= b.pos.x >= entry;
+            if !entered {
+                assert_eq!(*a.vel, *b.vel);
+            } else if b.pos.x > entry + 0.01 && b.vel.x > 0.0 {
+                assert!(b.vel.x < prev_vel);
+                assert!(b.vel.x < a.vel.x);
+                decelerated = true;
+            }
+            prev_vel = b.vel.x;
+        }
+        assert!(decelerated);
+    }
+
+    #[test]
+    fn adaptive_matches_fixed_step_drop() {
+        let drop = || {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            let mut item = ball(0.05, Vec2::new(-0.3, -0.5));
+            item.vel = Var::new(Vec2::new(0.5, -0.2));
+            world.insert_item(item);
+            world
+        };
+        let mut fixed = drop();
+        fixed.advance(&mut Rk4, 0.3);
+        let mut adaptive = drop();
+        let stats = adaptive.solve_adaptive_rkf(0.3, 1e-5);
+
+        assert_eq!(adaptive.step_count(), stats.accepted as u64);
+        assert!((adaptive.elapsed() - fixed.elapsed()).abs() < 1e-5);
+        let (a, b) = (adaptive.item(0).unwrap(), fixed.item(0).unwrap());
+        assert!(
+            a.pos.distance(*b.pos) < 1e-3,
+            "{:?} != {:?}",
+            *a.pos,
+            *b.pos
+        );
+        assert!(
+            a.vel.distance(*b.vel) < 1e-3,
+            "{:?} != {:?}",
+            *a.vel,
+            *b.vel
+        );
+    }
+
+    #[test]
+    fn stiff_collision_takes_more_substeps() {
+        let substeps = |speed: f32| {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            for dir in [-1.0, 1.0] {
+                let mut item = ball(0.1, Vec2::new(0.15 * dir, 0.0));
+                item.gravity_scale = 0.0;
+                item.vel = Var::new(Vec2::new(-speed * dir, 0.0));
+                world.insert_item(item);
+            }
+            world.solve_adaptive_rkf(0.2, 1e-4).accepted
+        };
+        assert!(substeps(2.0) > substeps(0.1));
+    }
+
+    #[test]
+    fn adaptive_respects_static_and_locks() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let mut fixed = ball(0.1, Vec2::new(-0.5, 0.0));
+        fixed.kind = BodyKind::Static;
+        fixed.vel = Var::new(Vec2::new(1.0, 0.0));
+        world.insert_item(fixed);
+        let mut locked = ball(0.1, Vec2::new(0.5, 0.0));
+        locked.lock_translation = true;
+        locked.asp = Var::new(3.0);
+        world.insert_item(locked);
+
+        world.solve_adaptive_rkf(0.2, 1e-4);
+        let (fixed, locked) = (world.item(0).unwrap(), world.item(1).unwrap());
+        assert_eq!(*fixed.pos, Vec2::new(-0.5, 0.0));
+        assert_eq!(*locked.pos, Vec2::new(0.5, 0.0));
+        assert!(locked.rot.angle().abs() > 0.1);
+        // Previous pose is kept for interpolation
+        assert_ne!(locked.prev_rot.angle(), locked.rot.angle());
+    }
+
+    #[test]
+    fn contact_stats_match_final_state() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let floor = world.wall_size().y;
+        world.insert_item(ball(0.1, Vec2::new(0.0, floor - 0.1)));
+        world.insert_item(ball(0.1, Vec2::new(0.15, floor - 0.1)));
+        world.insert_item(ball(0.1, Vec2::new(-0.5, 0.0)));
+        world.advance(&mut Rk4, DT);
+
+        let mut overlap = 0.0;
+        for i in 0..world.n_items() {
+            let contacts = world.contacts_for(i);
+            assert_eq!(world.item(i).unwrap().contacts, contacts.len());
+            for contact in contacts {
+                overlap += match contact.other {
+                    ContactTarget::Wall(_) => contact.area,
+                    // Each pair is seen from both sides
+                    ContactTarget::Item(_) => 0.5 * contact.area,
+                };
+            }
+        }
+        assert!(world.item(0).unwrap().contacts >= 2);
+        assert!((world.total_overlap() - overlap).abs() <= 1e-6 * overlap.max(1.0));
+        assert!(world.max_penetration() > 0.0);
+    }
+
+    #[test]
+    fn collecting_contacts_keeps_world() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let floor = world.wall_size().y;
+        world.insert_item(ball(0.1, Vec2::new(0.0, floor - 0.1)));
+        world.insert_item(ball(0.1, Vec2::new(0.15, floor - 0.1)));
+        world.advance(&mut Rk4, DT);
+        let derivs = |world: &World<Rk4>| {
+            world
+                .items()
+                .map(|item| (item.pos.deriv, item.vel.deriv, item.asp.deriv))
+                .collect::<Vec<_>>()
+        };
+        let (state, before) = (world.state_vector(), derivs(&world));
+
+        assert!(!world.collect_contacts().is_empty());
+        assert_eq!(world.state_vector(), state);
+        assert_eq!(derivs(&world), before);
+    }
+
+    #[test]
+    fn impacts_cover_all_touching_pairs() {
+        let mut world = World::<Rk4>::new(Vec2::new(0.5, 0.5));
+        for _ in 0..30 {
+            world.spawn_random();
+        }
+        run(&mut world, 0.2);
+
+        let mut expected = Vec::new();
+        for i in 0..world.items.len() {
+            for j in (i + 1)..world.items.len() {
+                if detect_collision(&world.items[i], &world.items[j]).is_some() {
+                    expected.push((i, j));
+                }
+            }
+        }
+        let impacts = world
+            .impacts()
+            .into_iter()
+            .filter_map(|(i, other, ..)| match other {
+                ContactTarget::Item(j) => Some((i, j)),
+                ContactTarget::Wall(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(!impacts.is_empty());
+        assert_eq!(impacts, expected);
+    }
+
+    #[test]
+    fn time_scale_keeps_trajectory() {
+        let thrown = |time_scale: f32| {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            world.set_time_scale(time_scale);
+            let mut item = ball(0.05, Vec2::new(-0.5, -0.5));
+            item.gravity_scale = 0.1;
+            item.vel = Var::new(Vec2::new(0.2, 0.0));
+            item.asp = Var::new(2.0);
+            world.insert_item(item);
+            world
+        };
+        let mut fast = thrown(2.0);
+        run(&mut fast, 1.0);
+        let mut normal = thrown(1.0);
+        run(&mut normal, 2.0);
+
+        assert!((fast.elapsed() - normal.elapsed()).abs() < 1e-4);
+        let (a, b) = (fast.item(0).unwrap(), normal.item(0).unwrap());
+        assert!(
+            a.pos.distance(*b.pos) < 1e-4,
+            "{:?} != {:?}",
+            *a.pos,
+            *b.pos
+        );
+        assert!(
+            a.vel.distance(*b.vel) < 1e-4,
+            "{:?} != {:?}",
+            *a.vel,
+            *b.vel
+        );
+        assert!((a.rot.angle() - b.rot.angle()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn advance_ignores_invalid_time() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.insert_item(ball(0.1, Vec2::ZERO));
+        for dt in [f32::NAN, f32::INFINITY, -DT] {
+            world.advance(&mut Rk4, dt);
+        }
+        assert_eq!(world.step_count(), 0);
+        assert_eq!(*world.item(0).unwrap().pos, Vec2::ZERO);
+
+        // Huge time scale is limited by sub-step count
+        world.set_time_scale(1e30);
+        world.advance(&mut Rk4, DT);
+        assert_eq!(world.step_count(), MAX_SUBSTEPS as u64);
+    }
+
+    #[test]
+    fn ball_rolls_down_incline() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let (angle, center) = (0.3f32, Vec2::new(0.0, 0.3));
+        // Y axis points down, so moving along the tangent goes downhill
+        let (tangent, normal) = (Vec2::from_angle(angle), Vec2::from_angle(angle).perp());
+        let mut slope = Item::<Rk4>::new(
+            Shape::Rectangle {
+                size: Vec2::new(0.8, 0.05),
+            },
+            center,
+            Rgb::new(1.0, 1.0, 1.0),
+        );
+        slope.kind = BodyKind::Static;
+        slope.set_pose(center, Rot2::from_angle(angle));
+        world.insert_item(slope);
+
+        let radius = 0.1;
+        let mut item = ball(radius, center - 0.4 * tangent - (0.05 + radius) * normal);
+        item.material = Material {
+            friction_model: FrictionModel::Coulomb,
+            ..Material::default()
+        };
+        world.insert_item(item);
+        run(&mut world, 0.5);
+
+        let item = world.item(1).unwrap();
+        let speed = item.vel.dot(tangent);
+        assert!(speed > 0.1, "{speed}");
+        // Contact point stays still while rolling
+        let slip = item.vel_at(*item.pos + radius * normal).dot(tangent);
+        assert!(slip.abs() < 0.2 * speed, "slip {slip} at speed {speed}");
+        assert!((item.asp.abs() * radius - speed).abs() < 0.2 * speed);
+    }
+
+    #[test]
+    fn contacts_for_reports_touching_bodies_only() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let floor = world.wall_size().y;
+        world.insert_item(ball(0.1, Vec2::new(0.0, floor - 0.09)));
+        world.insert_item(ball(0.1, Vec2::new(0.15, floor - 0.09)));
+        world.insert_item(ball(0.1, Vec2::new(-0.5, 0.0)));
+        let state = world.state_vector();
+
+        let contacts = world.contacts_for(0);
+        assert_eq!(contacts.len(), 2);
+        for contact in &contacts {
+            assert!(contact.area > 0.0);
+            match contact.other {
+                ContactTarget::Wall(side) => {
+                    assert_eq!(side, WallSide::Bottom);
+                    assert!(contact.normal.distance(Vec2::new(0.0, -1.0)) < 1e-4);
+                }
+                ContactTarget::Item(j) => {
+                    assert_eq!(j, 1);
+                    assert!(contact.normal.distance(Vec2::new(-1.0, 0.0)) < 1e-4);
+                }
+            }
+        }
+        assert!(world.contacts_for(2).is_empty());
+        assert!(world.contacts_for(3).is_empty());
+        // Querying contacts applies no forces
+        assert_eq!(world.state_vector(), state);
+        assert!(world.items().all(|item| *item.vel == Vec2::ZERO));
+    }
+
+    /// Total overlap of a settled stack of three boxes.
+    fn stack_overlap(correction: f32) -> f32 {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_position_correction(correction);
+        let floor = world.wall_size().y;
+        for k in 0..3 {
+            world.insert_item(square(0.05, Vec2::new(0.0, floor - 0.05 - 0.1 * k as f32)));
+        }
+        run(&mut world, 3.0);
+        world.total_overlap()
+    }
+
+    #[test]
+    fn position_correction_reduces_sinking() {
+        assert!(stack_overlap(0.5) < stack_overlap(0.0));
+    }
+
+    #[test]
+    fn position_correction_respects_masses() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_position_correction(1.0);
+        let (a, b, c) = (
+            Vec2::new(-0.08, 0.0),
+            Vec2::new(0.08, 0.0),
+            Vec2::new(0.5, 0.0),
+        );
+        let mut heavy = ball(0.1, a);
+        heavy.mass *= 3.0;
+        world.insert_item(heavy);
+        world.insert_item(ball(0.1, b));
+        let mut fixed = ball(0.1, c);
+        fixed.kind = BodyKind::Static;
+        world.insert_item(fixed);
+        world.insert_item(ball(0.1, c + Vec2::new(0.0, 0.15)));
+        world.correct_positions();
+
+        let shift = |i: usize, pos: Vec2| world.item(i).unwrap().pos.distance(pos);
+        assert!(shift(1, b) > 0.0);
+        assert!((shift(1, b) - 3.0 * shift(0, a)).abs() < 1e-4);
+        assert_eq!(shift(2, c), 0.0);
+        assert!(shift(3, c + Vec2::new(0.0, 0.15)) > 0.0);
+    }
+
+    #[test]
+    fn gravity_scale_sets_weight() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for (x, scale) in [(-0.5, 1.0), (0.0, 0.0), (0.5, -1.0)] {
+            let mut item = ball(0.05, Vec2::new(x, 0.0));
+            item.gravity_scale = scale;
+            world.insert_item(item);
+        }
+        run(&mut world, 0.2);
+
+        let vel = |i: usize| world.item(i).unwrap().vel.y;
+        // Y axis points down
+        assert!(vel(0) > 0.1);
+        assert_eq!(vel(1), 0.0);
+        assert!(vel(2) < -0.1);
+        assert!((vel(0) + vel(2)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn center_of_mass_and_bounds() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        assert!(world.bounds().is_none());
+        assert!(world.center_of_mass().is_none());
+        for (pos, mass) in [
+            (Vec2::new(-0.5, 0.0), 1.0),
+            (Vec2::new(0.5, 0.0), 3.0),
+            (Vec2::new(0.0, 0.4), 4.0),
+        ] {
+            let mut item = ball(0.1, pos);
+            item.mass = mass;
+            world.insert_item(item);
+        }
+        // (1 * -0.5 + 3 * 0.5, 4 * 0.4) / 8
+        let center = world.center_of_mass().unwrap();
+        assert!(center.distance(Vec2::new(0.125, 0.2)) < 1e-6, "{center:?}");
+        let (min, max) = world.bounds().unwrap();
+        assert!(min.distance(Vec2::new(-0.6, -0.1)) < 1e-6);
+        assert!(max.distance(Vec2::new(0.6, 0.5)) < 1e-6);
+    }
+
+    #[test]
+    fn terminal_velocity_of_drag_models() {
+        let item = ball(0.1, Vec2::ZERO);
+        let weight = GRAV.y * item.mass;
+        let linear = weight / (AIRF * 0.1);
+        let quadratic = (weight / (AIRF2 * 0.1)).sqrt();
+        // Quadratic drag stops fast objects sooner
+        assert!(quadratic < linear);
+
+        for (model, terminal) in [
+            (DragModel::Linear, linear),
+            (DragModel::Quadratic, quadratic),
+        ] {
+            let accel = |speed: f32| {
+                let mut world = World::<Rk4>::new(Vec2::ONE);
+                world.set_drag_model(model);
+                let mut item = item.clone();
+                item.vel = Var::new(Vec2::new(0.0, speed));
+                world.insert_item(item);
+                world.derivative_vector()[3]
+            };
+            assert!(accel(terminal).abs() < 1e-3 * GRAV.y, "{model:?}");
+            assert!(accel(0.5 * terminal) > 0.0);
+            assert!(accel(2.0 * terminal) < 0.0);
+        }
+    }
+
+    #[test]
+    fn light_ball_floats_up_and_settles() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        // Water fills the lower half, Y axis points down
+        world.add_fluid_region(Vec2::new(-1.0, 0.0), Vec2::new(1.0, 1.0), 10.0);
+        let radius = 0.1;
+        world.insert_item(ball(radius, Vec2::new(0.0, 0.6)));
+        run(&mut world, 1.0);
+        assert!(world.item(0).unwrap().pos.y < 0.6);
+        run(&mut world, 4.0);
+
+        let item = world.item(0).unwrap();
+        // Floats partially submerged
+        assert!(item.pos.y.abs() < radius, "{:?}", *item.pos);
+        assert!(item.vel.length() < 0.05, "{:?}", *item.vel);
+    }
+
+    #[test]
+    fn charges_attract_and_repel() {
+        let gap = |charges: (f32, f32)| {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            for (x, charge) in [(-0.3, charges.0), (0.3, charges.1)] {
+                let mut item = ball(0.05, Vec2::new(x, 0.0));
+                item.gravity_scale = 0.0;
+                item.charge = charge;
+                world.insert_item(item);
+            }
+            run(&mut world, 0.1);
+            let (a, b) = (world.item(0).unwrap(), world.item(1).unwrap());
+            // Forces are equal and opposite
+            assert!((a.vel.x + b.vel.x).abs() <= 1e-4 * a.vel.x.abs());
+            assert_eq!((a.vel.y, b.vel.y), (0.0, 0.0));
+            b.pos.x - a.pos.x
+        };
+        assert!(gap((0.1, -0.1)) < 0.6);
+        assert!(gap((0.1, 0.1)) > 0.6);
+        assert!(gap((-0.1, -0.1)) > 0.6);
+        assert_eq!(gap((0.1, 0.0)), 0.6);
+    }
+
+    #[test]
+    fn field_pushes_weightless_body() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_field(Vec2::new(1.0, 0.0));
+        let mut item = ball(0.05, Vec2::ZERO);
+        item.gravity_scale = 0.0;
+        world.insert_item(item);
+        run(&mut world, 0.5);
+
+        let item = world.item(0).unwrap();
+        // Field acceleration doesn't depend on mass
+        assert!((item.vel.x - 0.5).abs() < 0.01, "{:?}", *item.vel);
+        assert!(item.pos.x > 0.1);
+        assert_eq!(item.vel.y, 0.0);
+    }
+
+    #[test]
+    fn angular_speed_is_clamped() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for (x, asp) in [(-0.5, 1e4), (0.0, -1e4), (0.5, 1.0)] {
+            let mut item = ball(0.05, Vec2::new(x, 0.0));
+            item.asp = Var::new(asp);
+            world.insert_item(item);
+        }
+        world.advance(&mut Rk4, DT);
+        assert!(world.item(0).unwrap().asp.abs() > 100.0);
+
+        world.set_max_angular_speed(Some(10.0));
+        world.advance(&mut Rk4, DT);
+        assert_eq!(*world.item(0).unwrap().asp, 10.0);
+        assert_eq!(*world.item(1).unwrap().asp, -10.0);
+        // Slow items are not affected
+        assert!(world.item(2).unwrap().asp.abs() < 1.0 && *world.item(2).unwrap().asp > 0.9);
+    }
+
+    #[test]
+    fn more_substeps_resolve_collision_better() {
+        // Penetrations at the end of each step and the final position
+        let collide = |n: usize| {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            let mut item = ball(0.1, Vec2::new(0.5, 0.0));
+            item.gravity_scale = 0.0;
+            item.vel = Var::new(Vec2::new(3.0, 0.0));
+            world.insert_item(item);
+            let mut penetrations = Vec::new();
+            for _ in 0..10 {
+                world.solve_fixed_substeps(&mut Rk4, 0.05, n);
+                penetrations.push(world.max_penetration());
+            }
+            (penetrations, *world.item(0).unwrap().pos)
+        };
+        let (reference, reference_pos) = collide(64);
+        assert!(reference.iter().any(|p| *p > 0.0));
+        let error = |n: usize| {
+            let (penetrations, pos) = collide(n);
+            let max_error = penetrations
+                .iter()
+                .zip(&reference)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0f32, f32::max);
+            (max_error, pos.distance(reference_pos))
+        };
+        let (coarse, fine) = (error(1), error(4));
+        assert!(fine.0 < coarse.0, "{fine:?} vs {coarse:?}");
+        assert!(fine.1 < coarse.1, "{fine:?} vs {coarse:?}");
     }
 }
@@ -0,0 +1,82 @@
+use crate::World;
+use phy::Solver;
+use std::time::Duration;
+
+/// Weight of the last step in the rolling average of timings.
+const TIMING_SMOOTHING: f32 = 0.1;
+
+/// Wall-clock time spent in simulation phases of [`World::advance`].
+///
+/// Phases together cover the whole sub-steps, but not the [adaptive](World::solve_adaptive_rkf) solver.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct StepTimings {
+    /// Collecting pairs of items with overlapping bounding boxes in all derivative passes.
+    ///
+    /// Sweep along one axis is used, so it is still quadratic when many items are lined up across it.
+    pub broadphase: Duration,
+    /// Exact intersection of candidate pairs in all derivative passes.
+    pub narrowphase: Duration,
+    /// Solver steps excluding contact detection, includes walls, body forces, joints and drag.
+    pub integration: Duration,
+    /// Position correction, welding, events and spawning after solver steps.
+    ///
+    /// Contact detection performed there is counted here too.
+    pub post_step: Duration,
+}
+
+impl StepTimings {
+    /// Time spent on contact detection.
+    pub fn detection(&self) -> Duration {
+        self.broadphase + self.narrowphase
+    }
+    pub fn total(&self) -> Duration {
+        self.detection() + self.integration + self.post_step
+    }
+
+    /// Move towards `other` by `t` fraction.
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let lerp = |a: Duration, b: Duration| a.mul_f32(1.0 - t) + b.mul_f32(t);
+        Self {
+            broadphase: lerp(self.broadphase, other.broadphase),
+            narrowphase: lerp(self.narrowphase, other.narrowphase),
+            integration: lerp(self.integration, other.integration),
+            post_step: lerp(self.post_step, other.post_step),
+        }
+    }
+}
+
+impl<S: Solver> World<S> {
+    /// Rolling average of timings of [`Self::advance`] calls.
+    pub fn timing_stats(&self) -> StepTimings {
+        self.timing_stats
+    }
+
+    pub(crate) fn begin_timings(&mut self) {
+        self.timings = StepTimings::default();
+    }
+    pub(crate) fn end_timings(&mut self) {
+        self.timing_stats = self.timing_stats.lerp(self.timings, TIMING_SMOOTHING);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phy::Rk4;
+
+    #[test]
+    fn timings_are_populated() {
+        let mut world = World::<Rk4>::default();
+        for _ in 0..20 {
+            world.spawn_random();
+        }
+        assert_eq!(world.timing_stats(), StepTimings::default());
+        for _ in 0..10 {
+            world.advance(&mut Rk4, 1.0 / 60.0);
+        }
+        let stats = world.timing_stats();
+        assert!(stats.broadphase > Duration::ZERO);
+        assert!(stats.integration > Duration::ZERO);
+        assert!(stats.post_step > Duration::ZERO);
+    }
+}
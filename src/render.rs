@@ -0,0 +1,526 @@
+use crate::{
+    Body, Collider, Item, Shape, TextureId, World,
+    draw::{BORDERX, DrawMode, RenderCommand, SEGMENTS_RADIUS, layer_color},
+    physics::Actor,
+};
+use glam::{Affine2, Vec2, Vec4, Vec4Swizzles};
+use phy::Solver;
+use rand::Rng;
+use rand_distr::Uniform;
+use rgb::Rgb;
+use std::{error::Error, fmt, io};
+use wgame::{
+    Library,
+    fs::Path,
+    gfx::{
+        Scene,
+        types::{Color, color},
+    },
+    image::Image,
+    prelude::*,
+    texture::{Texture, TextureSettings},
+};
+
+/// Add drawing command to the scene.
+fn add_command(
+    lib: &Library,
+    scene: &mut Scene,
+    textures: &TextureStorage,
+    command: &RenderCommand,
+) {
+    match *command {
+        RenderCommand::Quad {
+            transform,
+            texture_id,
+            color,
+            order,
+        } => {
+            let quad = lib.shapes().unit_quad().transform(transform);
+            match texture_id.and_then(|id| textures.get(id)) {
+                Some(texture) => {
+                    scene.add(
+                        &quad
+                            .fill_texture(texture)
+                            .multiply_color(color)
+                            .order(order),
+                    );
+                }
+                None => {
+                    scene.add(&quad.fill_color(color).order(order));
+                }
+            }
+        }
+        RenderCommand::Rect {
+            min,
+            max,
+            color,
+            order,
+        } => {
+            scene.add(
+                &lib.shapes()
+                    .rectangle((min, max))
+                    .fill_color(color)
+                    .order(order),
+            );
+        }
+        RenderCommand::Line {
+            a,
+            b,
+            thickness,
+            color,
+            order,
+        } => {
+            // Thin quad extended by half of thickness to join adjacent segments
+            let dir = b - a;
+            scene.add(
+                &lib.shapes()
+                    .unit_quad()
+                    .transform(Affine2::from_scale_angle_translation(
+                        0.5 * Vec2::new(dir.length() + thickness, thickness),
+                        dir.to_angle(),
+                        0.5 * (a + b),
+                    ))
+                    .fill_color(color)
+                    .order(order),
+            );
+        }
+    }
+}
+
+impl<S: Solver> Item<S> {
+    /// Draw item, `circle_segments` is the number of debug circle outline segments for [`SEGMENTS_RADIUS`].
+    pub fn draw(
+        &self,
+        lib: &Library,
+        scene: &mut Scene,
+        textures: &TextureStorage,
+        mode: DrawMode,
+        circle_segments: usize,
+    ) {
+        for command in self.render_commands(mode, circle_segments) {
+            add_command(lib, scene, textures, &command);
+        }
+    }
+}
+
+impl<S: Solver> World<S> {
+    pub fn draw(
+        &self,
+        lib: &Library,
+        scene: &mut Scene,
+        textures: &TextureStorage,
+        mode: DrawMode,
+    ) {
+        for command in self.render_commands(mode) {
+            add_command(lib, scene, textures, &command);
+        }
+    }
+
+    /// Draw items at [interpolated poses](Item::interpolated_pose) between last two steps.
+    pub fn draw_interpolated(
+        &self,
+        lib: &Library,
+        scene: &mut Scene,
+        textures: &TextureStorage,
+        mode: DrawMode,
+        alpha: f32,
+    ) {
+        for command in self.render_commands_interpolated(mode, alpha) {
+            add_command(lib, scene, textures, &command);
+        }
+    }
+}
+
+/// Error of [debug rasterization](World::debug_raster_image).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RenderError {
+    /// Requested image has zero size.
+    EmptyImage,
+    /// World has zero size.
+    EmptyWorld,
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::EmptyImage => write!(f, "image size is zero"),
+            RenderError::EmptyWorld => write!(f, "world size is zero"),
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+impl<S: Solver> World<S> {
+    /// Approximate picture of the scene as an image of `(width, height)` pixels stretched to the whole world.
+    ///
+    /// This is a simple CPU rasterizer for debugging, not the `wgame` draw path: items are filled
+    /// with plain colors while textures, outlines, background decoration and forces are omitted,
+    /// so the result differs from what is shown in the window. It works headless and is deterministic,
+    /// so it is suitable for golden-image tests of physics output.
+    pub fn debug_raster_image(
+        &self,
+        size: (u32, u32),
+        mode: DrawMode,
+    ) -> Result<Image, RenderError> {
+        let pixels = self.debug_raster(size, mode)?;
+        Ok(Image::with_data(
+            size,
+            pixels
+                .into_iter()
+                .map(|c| Vec4::from((c.to_vec4().xyz(), 1.0)).to_rgba_f16())
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Pixel colors of [`Self::debug_raster_image`] row by row.
+    fn debug_raster(
+        &self,
+        (width, height): (u32, u32),
+        mode: DrawMode,
+    ) -> Result<Vec<Rgb<f32>>, RenderError> {
+        if width == 0 || height == 0 {
+            return Err(RenderError::EmptyImage);
+        }
+        if self.size.min_element() <= 0.0 {
+            return Err(RenderError::EmptyWorld);
+        }
+        let resolution = Vec2::new(width as f32, height as f32);
+        let to_world = |x: usize, y: usize| {
+            ((Vec2::new(x as f32, y as f32) + 0.5) / resolution * 2.0 - 1.0) * self.size
+        };
+        let to_pixel = |p: Vec2| (p / self.size + 1.0) * 0.5 * resolution;
+
+        let outside = self.background(mode);
+        let inside = match mode {
+            DrawMode::Normal | DrawMode::LayerView => self.theme.wall,
+            DrawMode::Debug => outside,
+        };
+        let wall_size = self.wall_size();
+        let mut pixels = (0..(width as usize * height as usize))
+            .map(|i| {
+                let pos = to_world(i % width as usize, i / width as usize);
+                if pos.abs().cmple(wall_size).all() {
+                    inside
+                } else {
+                    outside
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut parts = self
+            .items
+            .iter()
+            .flat_map(|item| {
+                item.world_parts().into_iter().map(|(part, pos, angle)| {
+                    let collider = Collider {
+                        shape: part.shape,
+                        pos,
+                        angle,
+                    };
+                    let color = match mode {
+                        DrawMode::LayerView => layer_color(item.layer),
+                        _ => part.color,
+                    };
+                    (collider, color, item.order)
+                })
+            })
+            .collect::<Vec<_>>();
+        parts.sort_by_key(|(.., order)| *order);
+        for (collider, color, _) in parts {
+            let (min, max) = collider.aabb();
+            let (min, max) = (
+                to_pixel(min).floor().max(Vec2::ZERO),
+                to_pixel(max).ceil().min(resolution),
+            );
+            let rot = Vec2::from_angle(-collider.angle);
+            for y in (min.y as usize)..(max.y as usize) {
+                for x in (min.x as usize)..(max.x as usize) {
+                    let rel_pos = rot.rotate(to_world(x, y) - collider.pos);
+                    let hit = match &collider.shape {
+                        Shape::Circle { radius } => rel_pos.length() <= *radius,
+                        Shape::Rectangle { size } => rel_pos.abs().cmple(*size).all(),
+                        Shape::RoundedRectangle {
+                            size,
+                            corner_radius,
+                        } => {
+                            let r = corner_radius.clamp(0.0, size.min_element());
+                            (rel_pos.abs() - (*size - r)).max(Vec2::ZERO).length() <= r
+                        }
+                        Shape::Ring { outer, inner } => {
+                            (*inner..=*outer).contains(&rel_pos.length())
+                        }
+                        Shape::Compound { .. } => false,
+                    };
+                    if hit {
+                        pixels[y * width as usize + x] = color;
+                    }
+                }
+            }
+        }
+
+        Ok(pixels)
+    }
+}
+
+const FORCEX: f32 = 0.05;
+
+pub struct DrawActor<'a> {
+    pub lib: &'a Library,
+    pub scene: &'a mut Scene,
+}
+
+impl<S: Solver> Actor<S> for DrawActor<'_> {
+    fn apply(&mut self, _: &mut Body<S>, pos: Vec2, force: Vec2) {
+        let fpos = pos + FORCEX * force;
+        // Draw an arrow
+        self.scene.add(
+            &self
+                .lib
+                .shapes()
+                .triangle(
+                    fpos,
+                    pos - BORDERX * FORCEX * force.perp(),
+                    pos + BORDERX * FORCEX * force.perp(),
+                )
+                .fill_color(color::WHITE),
+        );
+    }
+}
+
+/// Error of loading an asset.
+#[derive(Debug)]
+pub struct LoadError {
+    /// Path of the asset failed to load.
+    pub path: String,
+    pub source: Box<dyn Error + Send + Sync>,
+}
+
+impl LoadError {
+    /// Whether the asset file doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        let mut source: Option<&(dyn Error + 'static)> = Some(self.source.as_ref());
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<io::Error>() {
+                return err.kind() == io::ErrorKind::NotFound;
+            }
+            source = err.source();
+        }
+        false
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to load '{}': {}", self.path, self.source)
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+fn noisy_texture<R: Rng>(
+    rng: R,
+    lib: &Library,
+    width: u32,
+    height: u32,
+    base: Rgb<f32>,
+    var: Rgb<f32>,
+) -> Texture {
+    lib.make_texture(
+        &Image::with_data(
+            (width, height),
+            rng.sample_iter(Uniform::new(0.0, 1.0).unwrap())
+                .take(width as usize * height as usize)
+                .map(|a| {
+                    Vec4::from(((base.to_vec4() + a * var.to_vec4()).xyz(), 1.0)).to_rgba_f16()
+                })
+                .collect::<Vec<_>>(),
+        ),
+        TextureSettings::nearest(),
+    )
+}
+
+/// Colors of `cells` by `cells` board of alternating colors row by row.
+fn checkerboard(cells: u32, a: Rgb<f32>, b: Rgb<f32>) -> impl Iterator<Item = Rgb<f32>> {
+    (0..(cells * cells)).map(move |i| {
+        if (i % cells + i / cells) % 2 == 0 {
+            a
+        } else {
+            b
+        }
+    })
+}
+
+/// Square texture with `cells` by `cells` board of alternating colors.
+fn checkerboard_texture(lib: &Library, cells: u32, a: Rgb<f32>, b: Rgb<f32>) -> Texture {
+    lib.make_texture(
+        &Image::with_data(
+            (cells, cells),
+            checkerboard(cells, a, b)
+                .map(|color| Vec4::from((color.to_vec4().xyz(), 1.0)).to_rgba_f16())
+                .collect::<Vec<_>>(),
+        ),
+        TextureSettings::nearest(),
+    )
+}
+
+/// What to do when a texture image cannot be loaded.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum MissingTexturePolicy {
+    /// Fail with [`LoadError`].
+    #[default]
+    Error,
+    /// Use a generated placeholder if the image file doesn't exist, warning is logged with `log` feature.
+    ///
+    /// Other errors, e.g. a corrupted image, are still returned.
+    Fallback,
+}
+
+impl MissingTexturePolicy {
+    /// Whether a placeholder should be used instead of failing with `err`.
+    fn use_placeholder(&self, err: &LoadError) -> bool {
+        *self == Self::Fallback && err.is_not_found()
+    }
+}
+
+/// Textures referred by [`TextureId`].
+///
+/// Default storage is empty, e.g. for scenes without textures.
+#[derive(Default)]
+pub struct TextureStorage {
+    textures: Vec<Texture>,
+}
+
+impl TextureStorage {
+    /// Load default textures, accessible by [`TextureId::BALL`] and [`TextureId::NOISE`].
+    ///
+    /// Missing image files are handled according to `on_missing`.
+    pub async fn load(
+        base: impl AsRef<Path>,
+        rng: &mut impl Rng,
+        lib: &Library,
+        on_missing: MissingTexturePolicy,
+    ) -> Result<Self, LoadError> {
+        let path = format!("{}/ball.png", base.as_ref());
+        let ball = match lib
+            .load_texture(path.clone(), TextureSettings::linear())
+            .await
+        {
+            Ok(texture) => texture,
+            Err(err) => {
+                let err = LoadError {
+                    path,
+                    source: err.into(),
+                };
+                if !on_missing.use_placeholder(&err) {
+                    return Err(err);
+                }
+                #[cfg(feature = "log")]
+                log::warn!("{err}, using placeholder");
+                checkerboard_texture(lib, 8, Rgb::new(1.0, 1.0, 1.0), Rgb::new(0.5, 0.5, 0.5))
+            }
+        };
+        let noise = noisy_texture(
+            rng,
+            lib,
+            32,
+            32,
+            Rgb::new(0.75, 0.75, 0.75),
+            Rgb::new(0.25, 0.25, 0.25),
+        );
+        Ok(Self {
+            textures: vec![ball, noise],
+        })
+    }
+
+    pub fn get(&self, id: TextureId) -> Option<&Texture> {
+        self.textures.get(id.0)
+    }
+    /// Add a new texture to the storage.
+    pub fn insert(&mut self, texture: Texture) -> TextureId {
+        self.textures.push(texture);
+        TextureId(self.textures.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phy::Rk4;
+
+    const RED: Rgb<f32> = Rgb::new(1.0, 0.0, 0.0);
+    const BLUE: Rgb<f32> = Rgb::new(0.0, 0.0, 1.0);
+
+    fn load_error(source: impl Error + Send + Sync + 'static) -> LoadError {
+        LoadError {
+            path: "ball.png".into(),
+            source: Box::new(source),
+        }
+    }
+
+    #[test]
+    fn placeholder_only_for_missing_files() {
+        use MissingTexturePolicy::Fallback;
+
+        let missing = load_error(io::Error::from(io::ErrorKind::NotFound));
+        assert!(missing.is_not_found());
+        assert!(Fallback.use_placeholder(&missing));
+        assert!(!MissingTexturePolicy::Error.use_placeholder(&missing));
+        // Not found error is found through the chain of sources
+        let nested = load_error(load_error(io::Error::from(io::ErrorKind::NotFound)));
+        assert!(Fallback.use_placeholder(&nested));
+
+        let denied = load_error(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(!Fallback.use_placeholder(&denied));
+        let corrupted = load_error(fmt::Error);
+        assert!(!Fallback.use_placeholder(&corrupted));
+    }
+
+    #[test]
+    fn placeholder_is_checkerboard() {
+        let (a, b) = (RED, BLUE);
+        let cells = checkerboard(3, a, b).collect::<Vec<_>>();
+        assert_eq!(cells, [a, b, a, b, a, b, a, b, a]);
+        let cells = checkerboard(2, a, b).collect::<Vec<_>>();
+        assert_eq!(cells, [a, b, b, a]);
+    }
+
+    #[test]
+    fn debug_raster_rejects_empty_sizes() {
+        let world = World::<Rk4>::new(Vec2::ONE);
+        assert_eq!(
+            world.debug_raster((0, 10), DrawMode::Normal).err(),
+            Some(RenderError::EmptyImage)
+        );
+        let world = World::<Rk4>::new(Vec2::ZERO);
+        assert_eq!(
+            world.debug_raster((10, 10), DrawMode::Normal).err(),
+            Some(RenderError::EmptyWorld)
+        );
+    }
+
+    #[test]
+    fn debug_raster_fills_items_in_order() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        let mut below = Item::new(Shape::Circle { radius: 0.3 }, Vec2::ZERO, RED);
+        below.order = 1;
+        let mut above = Item::new(Shape::Circle { radius: 0.1 }, Vec2::new(0.25, 0.0), BLUE);
+        above.order = 2;
+        world.insert_item(above);
+        world.insert_item(below);
+
+        let size = 40;
+        let pixels = world.debug_raster((size, size), DrawMode::Normal).unwrap();
+        assert_eq!(pixels.len(), (size * size) as usize);
+        let pixel = |x: usize, y: usize| pixels[y * size as usize + x];
+        // Pixel centers are at `(k + 0.5) / 20 - 1`
+        assert_eq!(pixel(0, 0), world.background(DrawMode::Normal));
+        assert_eq!(pixel(2, 2), world.theme.wall);
+        assert_eq!(pixel(20, 20), RED);
+        assert_eq!(pixel(25, 20), BLUE);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{Item, Shape, TextureId, TextureStorage, World, color_from_seed};
+use glam::Vec2;
+use phy::{Rot2, Solver, Var};
+use rgb::Rgb;
+use serde::Deserialize;
+use std::{error::Error, fmt};
+
+/// Half-sides of the world if not specified in the scene.
+const SCENE_SIZE: [f32; 2] = [1.0, 1.0];
+
+/// Hand-authored scene description.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneDesc {
+    #[serde(default = "scene_size")]
+    size: [f32; 2],
+    #[serde(default)]
+    objects: Vec<ObjectDesc>,
+}
+
+fn scene_size() -> [f32; 2] {
+    SCENE_SIZE
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectDesc {
+    pos: [f32; 2],
+    shape: ShapeDesc,
+    /// Random color is chosen if absent.
+    color: Option<[f32; 3]>,
+    #[serde(default)]
+    vel: [f32; 2],
+    /// Rotation angle in radians.
+    #[serde(default)]
+    angle: f32,
+    /// Index of texture in the storage, default texture of the shape is used if absent.
+    texture: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ShapeDesc {
+    Circle { radius: f32 },
+    Rectangle { size: [f32; 2] },
+    RoundedRectangle { size: [f32; 2], corner_radius: f32 },
+    Ring { outer: f32, inner: f32 },
+}
+
+impl From<ShapeDesc> for Shape {
+    fn from(desc: ShapeDesc) -> Self {
+        match desc {
+            ShapeDesc::Circle { radius } => Shape::Circle { radius },
+            ShapeDesc::Rectangle { size } => Shape::Rectangle { size: size.into() },
+            ShapeDesc::RoundedRectangle {
+                size,
+                corner_radius,
+            } => Shape::RoundedRectangle {
+                size: size.into(),
+                corner_radius,
+            },
+            ShapeDesc::Ring { outer, inner } => Shape::Ring { outer, inner },
+        }
+    }
+}
+
+/// Error of loading a scene description.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SceneError {
+    /// Malformed JSON, missing or unknown fields.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Object refers to a texture absent in the storage.
+    UnknownTexture { object: usize, texture: usize },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "scene parse error at {line}:{column}: {message}"),
+            SceneError::UnknownTexture { object, texture } => {
+                write!(f, "object {object} refers to unknown texture {texture}")
+            }
+        }
+    }
+}
+
+impl Error for SceneError {}
+
+impl From<serde_json::Error> for SceneError {
+    fn from(err: serde_json::Error) -> Self {
+        SceneError::Parse {
+            line: err.line(),
+            column: err.column(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl<S: Solver> World<S> {
+    /// Create world from a JSON scene description.
+    ///
+    /// The scene is an object with optional `size` half-sides and a list of `objects`, e.g.
+    ///
+    /// ```json
+    /// {
+    ///     "size": [1.0, 1.0],
+    ///     "objects": [
+    ///         { "pos": [0.0, 0.0], "shape": { "type": "circle", "radius": 0.1 }, "vel": [1.0, 0.0] },
+    ///         { "pos": [0.5, 0.5], "shape": { "type": "rectangle", "size": [0.2, 0.1] }, "color": [1.0, 0.0, 0.0] }
+    ///     ]
+    /// }
+    /// ```
+    ///
+    /// Only `pos` and `shape` of an object are required, other fields are `color`, `vel`, `angle` and `texture` index.
+    /// Shape `type` is one of `circle`, `rectangle`, `rounded_rectangle` and `ring` with the same fields as [`Shape`].
+    /// Unknown fields are rejected.
+    pub fn load_scene_json(json: &str, textures: &TextureStorage) -> Result<Self, SceneError> {
+        let desc: SceneDesc = serde_json::from_str(json)?;
+        let mut world = Self::new(desc.size.into());
+        for (i, object) in desc.objects.into_iter().enumerate() {
+            let color = match object.color {
+                Some([r, g, b]) => Rgb::new(r, g, b),
+                None => color_from_seed(i as u64),
+            };
+            let mut item = Item::new(object.shape.into(), object.pos.into(), color);
+            if let Some(texture) = object.texture {
+                if textures.get(TextureId(texture)).is_none() {
+                    return Err(SceneError::UnknownTexture { object: i, texture });
+                }
+                item.texture = Some(TextureId(texture));
+            }
+            item.vel = Var::new(object.vel.into());
+            item.set_pose(object.pos.into(), Rot2::from_angle(object.angle));
+            world.insert_item(item);
+        }
+        Ok(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shape;
+    use phy::Rk4;
+
+    const TWO_OBJECTS: &str = r#"{
+        "size": [2.0, 1.0],
+        "objects": [
+            { "pos": [0.5, 0.25], "shape": { "type": "circle", "radius": 0.1 }, "vel": [1.0, -2.0] },
+            {
+                "pos": [-0.5, 0.0],
+                "shape": { "type": "rectangle", "size": [0.2, 0.1] },
+                "color": [1.0, 0.0, 0.0],
+                "angle": 0.5
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn load_two_objects() {
+        let world = World::<Rk4>::load_scene_json(TWO_OBJECTS, &TextureStorage::default()).unwrap();
+        assert_eq!(world.size(), Vec2::new(2.0, 1.0));
+        assert_eq!(world.n_items(), 2);
+
+        let ball = world.item(0).unwrap();
+        assert!(matches!(ball.shape, Shape::Circle { radius } if radius == 0.1));
+        assert_eq!(*ball.pos, Vec2::new(0.5, 0.25));
+        assert_eq!(*ball.vel, Vec2::new(1.0, -2.0));
+        assert_eq!(ball.color, color_from_seed(0));
+        assert_eq!(ball.texture, None);
+
+        let rect = world.item(1).unwrap();
+        assert!(matches!(rect.shape, Shape::Rectangle { size } if size == Vec2::new(0.2, 0.1)));
+        assert_eq!(*rect.pos, Vec2::new(-0.5, 0.0));
+        assert_eq!(*rect.vel, Vec2::ZERO);
+        assert!((rect.rot.angle() - 0.5).abs() < 1e-6);
+        assert_eq!(rect.color, Rgb::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn reject_invalid_scenes() {
+        let textures = TextureStorage::default();
+        let unknown_field = "{\n  \"objects\": [],\n  \"gravity\": 1.0\n}";
+        match World::<Rk4>::load_scene_json(unknown_field, &textures) {
+            Err(SceneError::Parse { line, .. }) => assert_eq!(line, 3),
+            _ => panic!("unknown field must be rejected"),
+        }
+
+        let unknown_texture = r#"{ "objects": [{ "pos": [0.0, 0.0], "shape": { "type": "circle", "radius": 0.1 }, "texture": 0 }] }"#;
+        assert!(matches!(
+            World::<Rk4>::load_scene_json(unknown_texture, &textures),
+            Err(SceneError::UnknownTexture {
+                object: 0,
+                texture: 0
+            })
+        ));
+    }
+}
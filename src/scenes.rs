@@ -0,0 +1,76 @@
+use crate::{Item, Joint, Shape, World, color_from_seed};
+use glam::Vec2;
+use phy::Solver;
+
+/// Length of Newton's cradle threads.
+const CRADLE_LENGTH: f32 = 0.6;
+/// Maximum radius of Newton's cradle balls.
+const CRADLE_RADIUS: f32 = 0.1;
+/// Damping of Newton's cradle pins.
+///
+/// Pins hold balls at the far end of threads, so they stop vibration of a ball about its pin
+/// without slowing down swinging.
+const CRADLE_DAMPING: f32 = 0.5;
+
+impl<S: Solver> World<S> {
+    /// Newton's cradle: a row of `count` touching equal balls hanging on pins.
+    ///
+    /// Contact damping is disabled, so collisions are nearly elastic, while pins are slightly damped.
+    /// Pull the first ball aside (e.g. by dragging) to start.
+    pub fn newtons_cradle(count: usize) -> Self {
+        let mut world = Self::new(Vec2::ONE);
+        world.physics_config_mut().damping = 0.0;
+        let count = count.max(1);
+        let radius = CRADLE_RADIUS.min(0.8 / count as f32);
+        let thread = Vec2::new(0.0, -CRADLE_LENGTH);
+        for k in 0..count {
+            let x = 2.0 * radius * (k as f32 - 0.5 * (count - 1) as f32);
+            let pos = Vec2::new(x, 0.5 * CRADLE_LENGTH);
+            let item = Item::new(Shape::Circle { radius }, pos, color_from_seed(k as u64));
+            world.insert_item(item);
+            world.add_joint(Joint {
+                damping: CRADLE_DAMPING,
+                ..Joint::pin(k, thread, pos + thread)
+            });
+        }
+        world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phy::{Rk4, Rot2};
+
+    #[test]
+    fn cradle_transfers_momentum() {
+        let mut world = World::<Rk4>::newtons_cradle(3);
+        // Pull the first ball aside around its pin
+        let item = world.item_mut(0).unwrap();
+        let anchor = *item.pos - Vec2::new(0.0, CRADLE_LENGTH);
+        let angle = 0.5;
+        let rot = Rot2::from_angle(angle);
+        item.set_pose(anchor + rot.transform(Vec2::new(0.0, CRADLE_LENGTH)), rot);
+
+        // Speed of the first ball right before the impact
+        let drop = CRADLE_LENGTH * (1.0 - angle.cos());
+        let impact_speed = (2.0 * 4.0 * drop).sqrt();
+        let (mut last_speed, mut first_speed) = (0.0f32, 0.0);
+        for _ in 0..60 {
+            world.advance(&mut Rk4, 1.0 / 60.0);
+            let last = world.item(2).unwrap().vel.x;
+            if last > last_speed {
+                last_speed = last;
+                first_speed = world.item(0).unwrap().vel.length();
+            }
+        }
+        assert!(
+            last_speed > 0.5 * impact_speed,
+            "{last_speed} <= {impact_speed} / 2"
+        );
+        assert!(
+            first_speed < 0.5 * last_speed,
+            "{first_speed} >= {last_speed} / 2"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{Shape, World};
+use glam::Vec2;
+use phy::{Rot2, Solver, Var};
+use std::f32::consts::{PI, TAU};
+
+/// Physical state of a single item.
+#[derive(Clone)]
+pub struct BodyState {
+    pub pos: Vec2,
+    pub vel: Vec2,
+    pub rot: Rot2,
+    pub asp: f32,
+    pub mass: f32,
+    pub inm: f32,
+    pub shape: Shape,
+}
+
+/// Physical state of all items without render data such as textures and colors.
+#[derive(Clone, Default)]
+pub struct PhysicsSnapshot {
+    pub items: Vec<BodyState>,
+}
+
+impl PhysicsSnapshot {
+    /// Interpolate motion state towards `other` by `t` fraction.
+    ///
+    /// Mass, moment of inertia and shape are taken from `self`.
+    /// Items missing in any of snapshots are skipped.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let items = self
+            .items
+            .iter()
+            .zip(&other.items)
+            .map(|(a, b)| {
+                let (a_angle, b_angle) = (a.rot.angle(), b.rot.angle());
+                let delta = (b_angle - a_angle + PI).rem_euclid(TAU) - PI;
+                BodyState {
+                    pos: a.pos.lerp(b.pos, t),
+                    vel: a.vel.lerp(b.vel, t),
+                    rot: Rot2::from_angle(a_angle + t * delta),
+                    asp: a.asp + t * (b.asp - a.asp),
+                    ..a.clone()
+                }
+            })
+            .collect();
+        Self { items }
+    }
+}
+
+impl<S: Solver> World<S> {
+    pub fn physics_snapshot(&self) -> PhysicsSnapshot {
+        let items = self
+            .items
+            .iter()
+            .map(|item| BodyState {
+                pos: *item.pos,
+                vel: *item.vel,
+                rot: *item.rot,
+                asp: *item.asp,
+                mass: item.mass,
+                inm: item.inm,
+                shape: item.shape.clone(),
+            })
+            .collect();
+        PhysicsSnapshot { items }
+    }
+
+    /// Overwrite physical state of items, render data and other properties are left intact.
+    ///
+    /// Returns `false` and does nothing if number of items differs.
+    pub fn apply_physics_snapshot(&mut self, snapshot: &PhysicsSnapshot) -> bool {
+        if snapshot.items.len() != self.items.len() {
+            return false;
+        }
+        for (item, state) in self.items.iter_mut().zip(&snapshot.items) {
+            item.pos = Var::new(state.pos);
+            item.vel = Var::new(state.vel);
+            item.rot = Var::new(state.rot);
+            item.asp = Var::new(state.asp);
+            item.mass = state.mass;
+            item.inm = state.inm;
+            item.shape = state.shape.clone();
+            item.prev_pos = state.pos;
+            item.prev_rot = state.rot;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Falloff, Joint, TextureId};
+    use phy::Rk4;
+    use rgb::Rgb;
+
+    /// Bit patterns of all values of the snapshot except shapes.
+    fn bits(snapshot: &PhysicsSnapshot) -> Vec<u32> {
+        snapshot
+            .items
+            .iter()
+            .flat_map(|s| {
+                [
+                    s.pos.x,
+                    s.pos.y,
+                    s.vel.x,
+                    s.vel.y,
+                    s.rot.angle(),
+                    s.asp,
+                    s.mass,
+                    s.inm,
+                ]
+            })
+            .map(f32::to_bits)
+            .collect()
+    }
+
+    fn busy_world() -> World<Rk4> {
+        let mut world = World::<Rk4>::new(Vec2::new(1.5, 1.0));
+        for _ in 0..30 {
+            world.spawn_random();
+        }
+        world.add_attractor(Vec2::new(0.5, 0.0), 1.0, Falloff::Linear { radius: 1.0 });
+        world.add_fluid_region(Vec2::new(-1.5, 0.5), Vec2::new(1.5, 1.0), 0.5);
+        world.add_joint(Joint::pin(0, Vec2::ZERO, Vec2::ZERO));
+        world.add_soft_body(Vec2::new(-0.5, -0.5), 0.2, 8, 50.0);
+        world
+    }
+
+    #[test]
+    fn identical_worlds_stay_identical() {
+        let (mut a, mut b) = (busy_world(), busy_world());
+        for _ in 0..120 {
+            a.advance(&mut Rk4, 1.0 / 60.0);
+            b.advance(&mut Rk4, 1.0 / 60.0);
+        }
+        assert_eq!(bits(&a.physics_snapshot()), bits(&b.physics_snapshot()));
+    }
+
+    #[test]
+    fn snapshot_round_trip_keeps_render_data() {
+        let mut world = busy_world();
+        world.advance(&mut Rk4, 1.0 / 60.0);
+        let snapshot = world.physics_snapshot();
+
+        for _ in 0..30 {
+            world.advance(&mut Rk4, 1.0 / 60.0);
+        }
+        let (color, texture) = (Rgb::new(0.1, 0.2, 0.3), Some(TextureId(7)));
+        for i in 0..world.n_items() {
+            let item = world.item_mut(i).unwrap();
+            item.color = color;
+            item.texture = texture;
+        }
+        assert_ne!(bits(&world.physics_snapshot()), bits(&snapshot));
+        assert!(world.apply_physics_snapshot(&snapshot));
+        assert_eq!(bits(&world.physics_snapshot()), bits(&snapshot));
+        assert!(
+            world
+                .items()
+                .all(|item| item.color == color && item.texture == texture)
+        );
+
+        let mut empty = World::<Rk4>::new(Vec2::ONE);
+        assert!(!empty.apply_physics_snapshot(&snapshot));
+    }
+}
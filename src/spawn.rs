@@ -0,0 +1,227 @@
+use crate::{Item, SampleParams, World, sample_item_with};
+use glam::Vec2;
+use phy::{Solver, Var};
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
+/// Interval between poured items in simulated seconds.
+const POUR_INTERVAL: f32 = 0.1;
+/// Initial downward speed of poured items.
+const POUR_SPEED: f32 = 1.0;
+/// Maximum horizontal speed of poured items.
+const POUR_SPREAD: f32 = 0.2;
+
+/// Identifier of a spawner added to the world.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SpawnerId(pub u64);
+
+/// Periodically creates sampled items at a fixed position.
+#[derive(Clone, Debug)]
+pub struct Spawner {
+    pub interval: f32,
+    pub pos: Vec2,
+    pub params: SampleParams,
+    /// Simulated time of the next spawn.
+    next: f64,
+}
+
+impl<S: Solver> World<S> {
+    /// Spawn an item sampled with `params` at `pos` every `interval` simulated seconds.
+    ///
+    /// Items are sampled using the world RNG. The first item is spawned immediately.
+    pub fn add_spawner(&mut self, interval: f32, pos: Vec2, params: SampleParams) -> SpawnerId {
+        let id = SpawnerId(self.next_spawner_id);
+        self.next_spawner_id += 1;
+        self.spawners.push((
+            id,
+            Spawner {
+                interval: interval.max(0.0),
+                pos,
+                params,
+                next: self.elapsed,
+            },
+        ));
+        id
+    }
+    /// Stop and remove the spawner, returns `None` if there is no such spawner.
+    pub fn remove_spawner(&mut self, id: SpawnerId) -> Option<Spawner> {
+        let index = self.spawners.iter().position(|(i, _)| *i == id)?;
+        Some(self.spawners.remove(index).1)
+    }
+    pub fn spawners(&self) -> impl ExactSizeIterator<Item = (SpawnerId, &Spawner)> + '_ {
+        self.spawners.iter().map(|(id, s)| (*id, s))
+    }
+
+    /// Sample random item with `params` using the world RNG.
+    fn sample(&mut self, params: &SampleParams) -> Item<S> {
+        self.n_spawned += 1;
+        sample_item_with(&mut self.rng, self.size, self.n_spawned, params)
+    }
+
+    /// Insert random item sampled using the world RNG, returns its index.
+    ///
+    /// Nothing is spawned in [degenerate](Self::is_degenerate) world.
+    pub fn spawn_random(&mut self) -> Option<usize> {
+        if self.is_degenerate() {
+            return None;
+        }
+        let item = self.sample(&SampleParams::default());
+        self.insert_item(item);
+        Some(self.items.len() - 1)
+    }
+    /// Insert random item sampled using the world RNG at `pos`, returns its index.
+    ///
+    /// Nothing is spawned in [degenerate](Self::is_degenerate) world.
+    pub fn spawn_random_at(&mut self, pos: Vec2) -> Option<usize> {
+        if self.is_degenerate() {
+            return None;
+        }
+        let item = self.sample(&SampleParams::default());
+        self.insert_item_at(item, pos);
+        Some(self.items.len() - 1)
+    }
+
+    /// Restart the world RNG from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+    }
+
+    /// Current state of the world RNG.
+    ///
+    /// Restoring it with [`Self::set_rng_state`] reproduces all subsequent random spawns.
+    pub fn rng_state(&self) -> SmallRng {
+        self.rng.clone()
+    }
+    pub fn set_rng_state(&mut self, rng: SmallRng) {
+        self.rng = rng;
+    }
+
+    pub fn spawn_cap(&self) -> Option<usize> {
+        self.spawn_cap
+    }
+    /// Spawners don't create items while there are at least `cap` items in the world.
+    pub fn set_spawn_cap(&mut self, cap: Option<usize>) {
+        self.spawn_cap = cap;
+    }
+
+    /// Drop `count` random items from the top edge one by one, falling down with slight horizontal spread.
+    ///
+    /// Items are created during subsequent steps at fixed simulated time intervals,
+    /// the first one on the next step.
+    pub fn pour(&mut self, count: usize) {
+        if self.pour_remaining == 0 {
+            self.next_pour = self.elapsed;
+        }
+        self.pour_remaining += count;
+    }
+    /// Number of items yet to be poured.
+    pub fn pour_remaining(&self) -> usize {
+        self.pour_remaining
+    }
+
+    /// Create the next poured item if its time has come.
+    fn run_pour(&mut self) {
+        if self.pour_remaining == 0 || self.next_pour > self.elapsed {
+            return;
+        }
+        self.pour_remaining -= 1;
+        self.next_pour = (self.next_pour + POUR_INTERVAL as f64).max(self.elapsed);
+        let item = self.sample(&SampleParams::default());
+        let wall_size = self.wall_size();
+        let margin = item.shape.radius();
+        let x_range = (wall_size.x - margin).max(0.0);
+        let pos = Vec2::new(
+            self.rng.random_range(-x_range..=x_range),
+            -wall_size.y + margin,
+        );
+        let vel = Vec2::new(
+            self.rng.random_range(-POUR_SPREAD..=POUR_SPREAD),
+            POUR_SPEED,
+        );
+        self.insert_item_at(item, pos);
+        if let Some(item) = self.items.last_mut() {
+            item.vel = Var::new(vel);
+        }
+    }
+
+    /// Create items of all spawners whose time has come.
+    pub(crate) fn run_spawners(&mut self) {
+        self.run_pour();
+        for k in 0..self.spawners.len() {
+            let spawner = &mut self.spawners[k].1;
+            if spawner.next > self.elapsed {
+                continue;
+            }
+            // At most one item per step, missed spawns are not made up later
+            spawner.next = (spawner.next + spawner.interval as f64).max(self.elapsed);
+            let (pos, params) = (spawner.pos, spawner.params.clone());
+            if self.spawn_cap.is_some_and(|cap| self.items.len() >= cap) {
+                continue;
+            }
+            let item = self.sample(&params);
+            self.insert_item_at(item, pos);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phy::Rk4;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn small_params() -> SampleParams {
+        SampleParams {
+            radius: (0.05, 0.05),
+            ..SampleParams::default()
+        }
+    }
+
+    fn run(world: &mut World<Rk4>, seconds: f32) {
+        for _ in 0..(seconds / DT).round() as usize {
+            world.advance(&mut Rk4, DT);
+        }
+    }
+
+    #[test]
+    fn spawner_creates_item_per_interval() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.add_spawner(1.0, Vec2::new(0.0, -0.5), small_params());
+        run(&mut world, 10.0);
+        assert!((10..=11).contains(&world.n_items()), "{}", world.n_items());
+    }
+
+    #[test]
+    fn spawner_respects_cap_and_removal() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_spawn_cap(Some(5));
+        let id = world.add_spawner(1.0, Vec2::new(0.0, -0.5), small_params());
+        run(&mut world, 10.0);
+        assert_eq!(world.n_items(), 5);
+
+        world.set_spawn_cap(None);
+        assert!(world.remove_spawner(id).is_some());
+        run(&mut world, 3.0);
+        assert_eq!(world.n_items(), 5);
+    }
+
+    #[test]
+    fn pour_enters_from_top() {
+        let mut world = World::<Rk4>::new(Vec2::new(2.0, 2.0));
+        world.pour(10);
+        let top = -world.wall_size().y;
+        for _ in 0..(2.0 / DT) as usize {
+            let n = world.n_items();
+            world.advance(&mut Rk4, DT);
+            // At most one item per step, placed just below the top wall and moving down
+            assert!(world.n_items() <= n + 1);
+            if world.n_items() > n {
+                let item = world.item(n).unwrap();
+                assert!((item.pos.y - (top + item.shape.radius())).abs() < 1e-4);
+                assert!(item.vel.y > 0.0);
+            }
+        }
+        assert_eq!(world.n_items(), 10);
+        assert_eq!(world.pour_remaining(), 0);
+    }
+}
@@ -0,0 +1,140 @@
+use glam::{Affine2, Vec2};
+
+/// Bounds of camera zoom factor.
+pub const ZOOM_RANGE: (f32, f32) = (1.0, 8.0);
+/// Default rate of approaching the followed point, per second.
+pub const FOLLOW_SMOOTHING: f32 = 4.0;
+
+/// Mapping between world coordinates and screen pixels with pan and zoom.
+#[derive(Clone, Copy, Debug)]
+pub struct WorldView {
+    /// Size of the screen in pixels corresponding to a unit of world half-size.
+    scale: f32,
+    /// Magnification relative to the whole world view.
+    zoom: f32,
+    /// World point at the center of the screen.
+    center: Vec2,
+    /// Rate of approaching the followed point, per second.
+    smoothing: f32,
+}
+
+impl WorldView {
+    pub fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            zoom: 1.0,
+            center: Vec2::ZERO,
+            smoothing: FOLLOW_SMOOTHING,
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+    pub fn zoom_factor(&self) -> f32 {
+        self.zoom
+    }
+    /// Screen size in pixels of a unit of world half-size with zoom applied, see [`crate::World::set_render_scale`].
+    pub fn render_scale(&self) -> f32 {
+        self.scale * self.zoom
+    }
+    pub fn center(&self) -> Vec2 {
+        self.center
+    }
+
+    /// Half of world sides that fit into the `viewport` without zoom.
+    pub fn world_size(&self, viewport: Vec2) -> Vec2 {
+        viewport / self.scale
+    }
+
+    /// Transformation from world to screen coordinates.
+    pub fn transform(&self, viewport: Vec2) -> Affine2 {
+        let scale = 0.5 * self.scale * self.zoom;
+        Affine2::from_scale_angle_translation(
+            Vec2::splat(scale),
+            0.0,
+            0.5 * viewport - scale * self.center,
+        )
+    }
+
+    pub fn world_to_screen(&self, pos: Vec2, viewport: Vec2) -> Vec2 {
+        self.transform(viewport).transform_point2(pos)
+    }
+    pub fn screen_to_world(&self, pos: Vec2, viewport: Vec2) -> Vec2 {
+        self.transform(viewport).inverse().transform_point2(pos)
+    }
+
+    /// Multiply zoom by `exp(delta)` keeping the world point under screen `focus` in place.
+    pub fn zoom(&mut self, delta: f32, focus: Vec2, viewport: Vec2) {
+        let target = self.screen_to_world(focus, viewport);
+        self.zoom = (self.zoom * delta.exp()).clamp(ZOOM_RANGE.0, ZOOM_RANGE.1);
+        self.center = target - (focus - 0.5 * viewport) / (0.5 * self.scale * self.zoom);
+        self.clamp_center(viewport);
+    }
+
+    /// Move the view by `delta` screen pixels.
+    pub fn pan(&mut self, delta: Vec2, viewport: Vec2) {
+        self.center -= delta / (0.5 * self.scale * self.zoom);
+        self.clamp_center(viewport);
+    }
+
+    pub fn follow_smoothing(&self) -> f32 {
+        self.smoothing
+    }
+    /// Set rate of approaching the followed point, larger values make the camera stiffer.
+    pub fn set_follow_smoothing(&mut self, smoothing: f32) {
+        self.smoothing = smoothing.max(0.0);
+    }
+
+    /// Smoothly move the center towards `target` world point during `dt` seconds.
+    pub fn follow(&mut self, target: Vec2, dt: f32, viewport: Vec2) {
+        let t = 1.0 - (-self.smoothing * dt).exp();
+        self.center = self.center.lerp(target, t);
+        self.clamp_center(viewport);
+    }
+
+    /// Reset zoom and center the world.
+    pub fn reset(&mut self) {
+        self.zoom = 1.0;
+        self.center = Vec2::ZERO;
+    }
+
+    /// Keep the view inside the world, so the world is centered when zoomed out.
+    fn clamp_center(&mut self, viewport: Vec2) {
+        let bound = self.world_size(viewport) * (1.0 - 1.0 / self.zoom);
+        self.center = self.center.clamp(-bound, bound);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_scale_follows_zoom() {
+        let viewport = Vec2::new(800.0, 600.0);
+        let mut view = WorldView::new(300.0);
+        assert_eq!(view.render_scale(), 300.0);
+        view.zoom(2.0_f32.ln(), 0.5 * viewport, viewport);
+        assert!((view.render_scale() - 600.0).abs() < 1e-3);
+        view.reset();
+        assert_eq!(view.render_scale(), 300.0);
+    }
+
+    #[test]
+    fn follow_converges_to_target() {
+        let viewport = Vec2::new(800.0, 600.0);
+        let mut view = WorldView::new(300.0);
+        // Zoom in so that the center is free to move
+        view.zoom(4.0_f32.ln(), 0.5 * viewport, viewport);
+        let target = Vec2::new(0.5, -0.3);
+        let mut dist = view.center().distance(target);
+        for _ in 0..120 {
+            view.follow(target, 1.0 / 60.0, viewport);
+            let next = view.center().distance(target);
+            assert!(next < dist);
+            dist = next;
+        }
+        assert!(dist < 1e-3, "{dist}");
+    }
+}
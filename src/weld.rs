@@ -0,0 +1,384 @@
+use crate::{Body, CompoundPart, Item, Shape, World, detect_collision};
+use glam::Vec2;
+use phy::{Rot2, Solver, Var, angular_to_linear2, torque2};
+use rand::Rng;
+use rand_distr::Uniform;
+use std::f32::consts::PI;
+
+/// Maximum speed of shattered pieces relative to the original item per unit of its radius.
+const SHATTER_SPREAD: f32 = 2.0;
+
+impl<S: Solver> Item<S> {
+    /// Parts of the item in world space as `(part, position, angle)`.
+    pub(crate) fn world_parts(&self) -> Vec<(CompoundPart, Vec2, f32)> {
+        self.parts_at(*self.pos, self.rot.angle())
+    }
+
+    /// Parts of the item placed at `pos` rotated by `angle` as `(part, position, angle)`.
+    pub(crate) fn parts_at(&self, pos: Vec2, angle: f32) -> Vec<(CompoundPart, Vec2, f32)> {
+        match &self.shape {
+            Shape::Compound { parts } => {
+                let rot = Vec2::from_angle(angle);
+                parts
+                    .iter()
+                    .map(|part| {
+                        (
+                            part.clone(),
+                            pos + rot.rotate(part.offset),
+                            angle + part.angle,
+                        )
+                    })
+                    .collect()
+            }
+            shape => vec![(
+                CompoundPart {
+                    shape: shape.clone(),
+                    offset: Vec2::ZERO,
+                    angle: 0.0,
+                    mass: self.mass,
+                    inm: self.inm,
+                    texture: self.texture,
+                    color: self.color,
+                },
+                pos,
+                angle,
+            )],
+        }
+    }
+}
+
+impl<S: Solver> World<S> {
+    /// Fuse `i`-th and `j`-th items into a single compound item preserving momentum.
+    ///
+    /// Compound item takes place of the `i`-th one and `j`-th item is removed.
+    /// Charges are summed, material, gravity scale and tag are taken from the `i`-th item.
+    /// Joints of both items are removed.
+    /// Returns `false` if there is no such items, they are the same or any of them is not [dynamic](Body::is_dynamic).
+    pub fn weld(&mut self, i: usize, j: usize) -> bool {
+        if i == j
+            || i >= self.items.len()
+            || j >= self.items.len()
+            || !self.items[i].is_dynamic()
+            || !self.items[j].is_dynamic()
+        {
+            return false;
+        }
+        let (a, b) = (&self.items[i], &self.items[j]);
+        let mass = a.mass + b.mass;
+        let pos = (a.mass * *a.pos + b.mass * *b.pos) / mass;
+        let vel = (a.mass * *a.vel + b.mass * *b.vel) / mass;
+        // Parallel axis theorem, angular momentum is taken about the new center
+        let charge = a.charge + b.charge;
+        let order = a.order.max(b.order);
+        let (mut inm, mut angular_momentum) = (0.0, 0.0);
+        for body in [a, b] {
+            let r = *body.pos - pos;
+            inm += body.inm + body.mass * r.length_squared();
+            angular_momentum += body.inm * *body.asp + body.mass * torque2(r, *body.vel - vel);
+        }
+        let parts = [a, b]
+            .into_iter()
+            .flat_map(Item::world_parts)
+            .map(|(part, part_pos, part_angle)| CompoundPart {
+                offset: part_pos - pos,
+                angle: part_angle,
+                ..part
+            })
+            .collect();
+
+        self.remove_joints_of(i);
+        let item = &mut self.items[i];
+        item.shape = Shape::Compound { parts };
+        item.mass = mass;
+        item.inm = inm;
+        item.charge = charge;
+        item.set_pose(pos, Rot2::default());
+        item.vel = Var::new(vel);
+        item.asp = Var::new(angular_momentum / inm);
+        item.order = order;
+        if matches!(self.drag, Some((k, ..)) if k == i) {
+            self.drag = None;
+        }
+        self.remove_item(j);
+        true
+    }
+
+    /// Split compound `i`-th item into separate items moving as they did in the compound.
+    ///
+    /// Parts are appended to the end of item list, other items are evicted first if the world is full.
+    /// Returns `false` if there is no such item or it is not compound.
+    pub fn unweld(&mut self, i: usize) -> bool {
+        let Some(Shape::Compound { .. }) = self.items.get(i).map(|item| &item.shape) else {
+            return false;
+        };
+        let compound = self.items.remove(i);
+        self.reindex_after_removal(i);
+        let parts = compound.world_parts();
+        self.make_room(parts.len());
+        for (part, pos, angle) in parts {
+            let mut item = compound.clone();
+            item.order = part.shape.default_order();
+            item.shape = part.shape;
+            // Charge is distributed proportionally to mass
+            item.charge = compound.charge * part.mass / compound.mass;
+            item.mass = part.mass;
+            item.inm = part.inm;
+            item.texture = part.texture;
+            item.color = part.color;
+            item.set_pose(pos, Rot2::from_angle(angle));
+            item.vel =
+                Var::new(*compound.vel + angular_to_linear2(*compound.asp, pos - *compound.pos));
+            item.asp = Var::new(*compound.asp);
+            self.items.push(item);
+        }
+        true
+    }
+
+    /// Weld pairs of touching items with welding material, detected once per sub-step from its final state.
+    pub(crate) fn apply_welds(&mut self) {
+        if !self.items.iter().any(|item| item.material.weld_on_contact) {
+            return;
+        }
+        let colliders = self.items.iter().map(Item::collider).collect::<Vec<_>>();
+        let mut welds = self
+            .candidate_pairs(&colliders)
+            .into_iter()
+            .filter(|&(i, j)| {
+                let (a, b) = (&self.items[i], &self.items[j]);
+                a.material.combine(&b.material).weld_on_contact && detect_collision(a, b).is_some()
+            })
+            .collect::<Vec<_>>();
+        while let Some((i, j)) = welds.pop() {
+            if !self.weld(i, j) {
+                continue;
+            }
+            // Item `j` is removed and merged into `i`
+            welds = welds
+                .into_iter()
+                .filter_map(|(a, b)| {
+                    let a = if a == j { i } else { a };
+                    let b = if b == j { i } else { b };
+                    let map = |k: usize| if k > j { k - 1 } else { k };
+                    Some((map(a), map(b))).filter(|(a, b)| a != b)
+                })
+                .collect();
+        }
+    }
+
+    /// Break `i`-th item into `pieces` smaller items of the same total mass.
+    ///
+    /// Circles and rings break into a ring of smaller circles, rectangles (also rounded) into strips along the longer side,
+    /// compound items into their parts. Pieces move with the original item plus random spread
+    /// with zero total momentum, and are appended to the end of item list.
+    /// If the world is full other items are evicted first, and there are no more pieces than [max items](Self::max_items).
+    /// Returns `false` if there is no such item, `pieces < 2` or the item size is zero or not finite.
+    pub fn shatter_item(&mut self, i: usize, pieces: usize) -> bool {
+        let pieces = self.max_items.map_or(pieces, |max| pieces.min(max));
+        if pieces < 2 || i >= self.items.len() {
+            return false;
+        }
+        if let Shape::Compound { .. } = self.items[i].shape {
+            return self.unweld(i);
+        }
+        let radius = self.items[i].shape.radius();
+        if !(radius.is_finite() && radius > 0.0) {
+            return false;
+        }
+        let original = self.items.remove(i);
+        self.reindex_after_removal(i);
+        self.make_room(pieces);
+
+        let (pos, rot) = (*original.pos, Vec2::from_angle(original.rot.angle()));
+        let n = pieces as f32;
+        // Shape and position of pieces relative to the original item
+        let layout = (0..pieces)
+            .map(|k| match &original.shape {
+                Shape::Circle { radius } => {
+                    // Ring of touching circles inscribed into the original one
+                    let piece_radius = radius / (1.0 + 1.0 / (PI / n).sin());
+                    let offset =
+                        (radius - piece_radius) * Vec2::from_angle(2.0 * PI * k as f32 / n);
+                    (
+                        Shape::Circle {
+                            radius: piece_radius,
+                        },
+                        offset,
+                    )
+                }
+                Shape::Rectangle { size } | Shape::RoundedRectangle { size, .. } => {
+                    let axis = if size.x >= size.y { Vec2::X } else { Vec2::Y };
+                    let length = 2.0 * (*size * axis).element_sum();
+                    let offset = ((k as f32 + 0.5) / n - 0.5) * length * axis;
+                    let piece_size = *size - (1.0 - 1.0 / n) * *size * axis;
+                    (Shape::Rectangle { size: piece_size }, offset)
+                }
+                Shape::Ring { outer, inner } => {
+                    // Circles along the ring
+                    let piece_radius =
+                        (0.5 * (outer - inner)).min(0.5 * (outer + inner) * (PI / n).sin());
+                    let offset = 0.5 * (outer + inner) * Vec2::from_angle(2.0 * PI * k as f32 / n);
+                    (
+                        Shape::Circle {
+                            radius: piece_radius,
+                        },
+                        offset,
+                    )
+                }
+                Shape::Compound { .. } => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        let max_spread = SHATTER_SPREAD * radius;
+        let mut spreads = layout
+            .iter()
+            .map(|(_, offset)| {
+                offset.normalize_or_zero()
+                    * self
+                        .rng
+                        .sample(Uniform::new_inclusive(0.0, max_spread).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let mean_spread = spreads.iter().sum::<Vec2>() / n;
+        spreads.iter_mut().for_each(|v| *v -= mean_spread);
+
+        for ((shape, offset), spread) in layout.into_iter().zip(spreads) {
+            let piece_pos = pos + rot.rotate(offset);
+            let mut item = original.clone();
+            item.mass = original.mass / n;
+            item.charge = original.charge / n;
+            item.inm = original.inm / n * shape.radius() / radius;
+            item.shape = shape;
+            let rot = *item.rot;
+            item.set_pose(piece_pos, rot);
+            item.vel = Var::new(
+                *original.vel + angular_to_linear2(*original.asp, piece_pos - pos) + spread,
+            );
+            self.items.push(item);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phy::Rk4;
+    use rgb::Rgb;
+
+    #[test]
+    fn touching_sticky_balls_weld() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for x in [-0.09, 0.09] {
+            let mut item = Item::new(
+                Shape::Circle { radius: 0.1 },
+                Vec2::new(x, 0.0),
+                Rgb::new(1.0, 1.0, 1.0),
+            );
+            item.material.weld_on_contact = true;
+            world.insert_item(item);
+        }
+        let mass = world.items().map(|item| item.mass).sum::<f32>();
+        world.advance(&mut Rk4, 1.0 / 60.0);
+
+        assert_eq!(world.n_items(), 1);
+        let item = world.item(0).unwrap();
+        assert!(matches!(item.shape, Shape::Compound { .. }));
+        assert!((item.mass - mass).abs() < 1e-6);
+    }
+
+    #[test]
+    fn shatter_conserves_mass_and_momentum() {
+        // Shapes with radii of their bounding circles
+        let shapes = [
+            (Shape::Circle { radius: 0.2 }, 0.2),
+            (
+                Shape::Rectangle {
+                    size: Vec2::new(0.3, 0.1),
+                },
+                Vec2::new(0.3, 0.1).length(),
+            ),
+            (
+                Shape::Ring {
+                    outer: 0.2,
+                    inner: 0.1,
+                },
+                0.2,
+            ),
+        ];
+        for (shape, bound) in shapes {
+            let mut world = World::<Rk4>::new(Vec2::ONE);
+            let mut item = Item::new(shape, Vec2::new(0.1, -0.2), Rgb::new(1.0, 1.0, 1.0));
+            item.vel = Var::new(Vec2::new(0.5, -0.3));
+            let (mass, momentum) = (item.mass, item.mass * *item.vel);
+            world.insert_item(item);
+
+            assert!(world.shatter_item(0, 5));
+            assert_eq!(world.n_items(), 5);
+            let pieces_mass = world.items().map(|item| item.mass).sum::<f32>();
+            let pieces_momentum = world
+                .items()
+                .map(|item| item.mass * *item.vel)
+                .sum::<Vec2>();
+            assert!((pieces_mass - mass).abs() < 1e-5 * mass);
+            assert!(pieces_momentum.distance(momentum) < 1e-4 * momentum.length());
+            for piece in world.items() {
+                assert!(piece.pos.distance(Vec2::new(0.1, -0.2)) <= bound);
+            }
+        }
+    }
+
+    /// Full world of balls along the x axis.
+    fn full_world(xs: &[f32]) -> World<Rk4> {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        world.set_max_items(Some(xs.len()));
+        for &x in xs {
+            world.insert_item(Item::new(
+                Shape::Circle { radius: 0.1 },
+                Vec2::new(x, 0.0),
+                Rgb::new(1.0, 1.0, 1.0),
+            ));
+        }
+        world
+    }
+
+    #[test]
+    fn shatter_respects_item_limit() {
+        let mut world = full_world(&[-0.6, -0.3, 0.0, 0.6]);
+        assert!(world.shatter_item(3, 3));
+        assert_eq!(world.n_items(), 4);
+        // Number of pieces is limited too
+        assert!(world.shatter_item(0, 10));
+        assert_eq!(world.n_items(), 4);
+    }
+
+    #[test]
+    fn shatter_rejects_degenerate_items() {
+        let mut world = World::<Rk4>::new(Vec2::ONE);
+        for radius in [0.0, f32::NAN, f32::INFINITY] {
+            let item = Item::new(
+                Shape::Circle { radius },
+                Vec2::ZERO,
+                Rgb::new(1.0, 1.0, 1.0),
+            );
+            world.insert_item(item);
+            assert!(!world.shatter_item(0, 4));
+            assert_eq!(world.n_items(), 1);
+            world.remove_item(0);
+        }
+    }
+
+    #[test]
+    fn unweld_respects_item_limit() {
+        let mut world = full_world(&[-0.6, -0.3, 0.0, 0.6]);
+        assert!(world.weld(0, 1));
+        assert_eq!(world.n_items(), 3);
+        world.set_max_items(Some(3));
+        assert!(world.unweld(0));
+        assert_eq!(world.n_items(), 3);
+        assert!(
+            world
+                .items()
+                .all(|item| !matches!(item.shape, Shape::Compound { .. }))
+        );
+    }
+}